@@ -0,0 +1,378 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use solana_sha256_hasher::hashv;
+
+use crate::config::{PauseError, ProgramConfig};
+use crate::transfer::transfer_in;
+
+// Domain separators so a leaf can never be passed off as an inner node
+pub const LEAF_PREFIX: &[u8] = &[0];
+pub const NODE_PREFIX: &[u8] = &[1];
+
+// Bitmap lives in the distributor account, which init can size up to 10 KiB
+pub const MAX_AIRDROP_CLAIMANTS: u64 = 80_000;
+
+// Merkle Airdrop Distributor Account
+#[account]
+pub struct Distributor {
+    pub id: u64,                         // Distributor ID
+    pub mint: Pubkey,                    // JDH Token mint
+    pub authority: Pubkey,               // Creator, receives clawback
+    pub vault: Pubkey,                   // PDA token account holding the airdrop
+    pub merkle_root: [u8; 32],           // Root of sha256(index, claimant, amount) leaves
+    pub total_amount: u64,               // Max total claimable
+    pub total_claimed: u64,              // Total claimed so far
+    pub num_claimants: u64,              // Number of leaves in the tree
+    pub num_claimed: u64,                // Number of leaves claimed
+    pub expires_at: i64,                 // Claims close, clawback opens
+    pub clawed_back: bool,               // Whether unclaimed funds were clawed back
+    pub bump: u8,                        // Bump seed
+    pub claimed_bitmap: Vec<u8>,         // One bit per leaf index
+}
+
+impl Distributor {
+    pub fn space(num_claimants: u64) -> usize {
+        8 + 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 4 + Self::bitmap_len(num_claimants)
+    }
+
+    pub fn bitmap_len(num_claimants: u64) -> usize {
+        num_claimants.div_ceil(8) as usize
+    }
+
+    pub fn is_claimed(&self, index: u64) -> bool {
+        self.claimed_bitmap[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u64) {
+        self.claimed_bitmap[(index / 8) as usize] |= 1 << (index % 8);
+    }
+}
+
+#[event]
+pub struct DistributorCreated {
+    pub distributor: Pubkey,
+    pub id: u64,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub num_claimants: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AirdropClaimed {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    pub total_claimed: u64,
+    pub num_claimed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AirdropClawedBack {
+    pub distributor: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+// Verify a proof using sorted pair hashing
+pub fn verify_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed = leaf;
+    for node in proof {
+        computed = if computed <= *node {
+            hashv(&[NODE_PREFIX, &computed, node]).to_bytes()
+        } else {
+            hashv(&[NODE_PREFIX, node, &computed]).to_bytes()
+        };
+    }
+    computed == root
+}
+
+// Create Distributor and fund its vault
+pub(crate) fn create(
+    ctx: Context<CreateDistributor>,
+    id: u64,
+    merkle_root: [u8; 32],
+    total_amount: u64,
+    num_claimants: u64,
+    expires_at: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(total_amount > 0, AirdropError::InvalidAmount);
+    require!(
+        num_claimants > 0 && num_claimants <= MAX_AIRDROP_CLAIMANTS,
+        AirdropError::InvalidClaimantCount
+    );
+    require!(expires_at > clock.unix_timestamp, AirdropError::InvalidExpiry);
+
+    let received = transfer_in(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.authority_token_account.to_account_info(),
+        &mut ctx.accounts.vault,
+        ctx.accounts.authority.to_account_info(),
+        &[],
+        total_amount,
+    )?;
+    // Allocations in the Merkle tree are exact, so a withheld transfer fee would leave the
+    // last claimants unfunded
+    require!(received == total_amount, AirdropError::FundingShortfall);
+
+    let distributor = &mut ctx.accounts.distributor;
+    distributor.id = id;
+    distributor.mint = ctx.accounts.mint.key();
+    distributor.authority = ctx.accounts.authority.key();
+    distributor.vault = ctx.accounts.vault.key();
+    distributor.merkle_root = merkle_root;
+    distributor.total_amount = total_amount;
+    distributor.total_claimed = 0;
+    distributor.num_claimants = num_claimants;
+    distributor.num_claimed = 0;
+    distributor.expires_at = expires_at;
+    distributor.clawed_back = false;
+    distributor.bump = ctx.bumps.distributor;
+    distributor.claimed_bitmap = vec![0; Distributor::bitmap_len(num_claimants)];
+
+    emit!(DistributorCreated {
+        distributor: distributor.key(),
+        id,
+        mint: distributor.mint,
+        authority: distributor.authority,
+        merkle_root,
+        total_amount,
+        num_claimants,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Claim an airdrop allocation with a Merkle proof
+pub(crate) fn claim(
+    ctx: Context<ClaimAirdrop>,
+    index: u64,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let distributor = &ctx.accounts.distributor;
+    let claimant = ctx.accounts.claimant.key();
+
+    require!(
+        clock.unix_timestamp < distributor.expires_at,
+        AirdropError::AirdropExpired
+    );
+    require!(index < distributor.num_claimants, AirdropError::InvalidIndex);
+    require!(!distributor.is_claimed(index), AirdropError::AlreadyClaimed);
+
+    let leaf = hashv(&[
+        LEAF_PREFIX,
+        &index.to_le_bytes(),
+        claimant.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes();
+    require!(
+        verify_proof(&proof, distributor.merkle_root, leaf),
+        AirdropError::InvalidProof
+    );
+
+    let total_claimed = distributor
+        .total_claimed
+        .checked_add(amount)
+        .ok_or(AirdropError::ExceedsTotalAmount)?;
+    require!(
+        total_claimed <= distributor.total_amount,
+        AirdropError::ExceedsTotalAmount
+    );
+
+    let id_bytes = distributor.id.to_le_bytes();
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.claimant_token_account.to_account_info(),
+                authority: distributor.to_account_info(),
+            },
+            &[&[
+                b"distributor",
+                distributor.mint.as_ref(),
+                id_bytes.as_ref(),
+                &[distributor.bump],
+            ]],
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let distributor = &mut ctx.accounts.distributor;
+    distributor.set_claimed(index);
+    distributor.total_claimed = total_claimed;
+    distributor.num_claimed += 1;
+
+    emit!(AirdropClaimed {
+        distributor: distributor.key(),
+        claimant,
+        index,
+        amount,
+        total_claimed,
+        num_claimed: distributor.num_claimed,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Claw back unclaimed funds after expiry
+pub(crate) fn clawback(ctx: Context<Clawback>) -> Result<()> {
+    let clock = Clock::get()?;
+    let distributor = &ctx.accounts.distributor;
+
+    require!(
+        clock.unix_timestamp >= distributor.expires_at,
+        AirdropError::AirdropNotExpired
+    );
+    require!(!distributor.clawed_back, AirdropError::AlreadyClawedBack);
+
+    let remaining = ctx.accounts.vault.amount;
+    if remaining > 0 {
+        let id_bytes = distributor.id.to_le_bytes();
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: distributor.to_account_info(),
+                },
+                &[&[
+                    b"distributor",
+                    distributor.mint.as_ref(),
+                    id_bytes.as_ref(),
+                    &[distributor.bump],
+                ]],
+            ),
+            remaining,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    let distributor = &mut ctx.accounts.distributor;
+    distributor.clawed_back = true;
+
+    emit!(AirdropClawedBack {
+        distributor: distributor.key(),
+        authority: distributor.authority,
+        amount: remaining,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64, merkle_root: [u8; 32], total_amount: u64, num_claimants: u64)]
+pub struct CreateDistributor<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = Distributor::space(num_claimants),
+        seeds = [b"distributor", mint.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distributor: Account<'info, Distributor>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"distributor-vault", distributor.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = distributor,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    #[account(mut, has_one = mint, has_one = vault)]
+    pub distributor: Account<'info, Distributor>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = claimant)]
+    pub claimant_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub claimant: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct Clawback<'info> {
+    #[account(mut, has_one = mint, has_one = vault, has_one = authority)]
+    pub distributor: Account<'info, Distributor>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[error_code]
+pub enum AirdropError {
+    #[msg("Airdrop amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Invalid number of claimants")]
+    InvalidClaimantCount,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Airdrop has expired")]
+    AirdropExpired,
+    #[msg("Airdrop has not expired yet")]
+    AirdropNotExpired,
+    #[msg("Claim index out of range")]
+    InvalidIndex,
+    #[msg("Allocation already claimed")]
+    AlreadyClaimed,
+    #[msg("Invalid Merkle proof")]
+    InvalidProof,
+    #[msg("Claim exceeds airdrop total")]
+    ExceedsTotalAmount,
+    #[msg("Unclaimed funds already clawed back")]
+    AlreadyClawedBack,
+    #[msg("Vault received less than the airdrop total")]
+    FundingShortfall,
+}
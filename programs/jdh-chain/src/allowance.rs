@@ -0,0 +1,333 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, Approve, Mint, Revoke, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::config::{PauseError, ProgramConfig};
+
+// Spending Allowance Account (owner grants a spender a per-period limit)
+// The program's delegate PDA is the SPL delegate of the owner's token account,
+// and only spends what an allowance permits.
+#[account]
+pub struct Allowance {
+    pub owner: Pubkey,                   // Token account owner
+    pub token_account: Pubkey,           // Owner's token account
+    pub mint: Pubkey,                    // JDH Token mint
+    pub spender: Pubkey,                 // dApp allowed to spend
+    pub limit_per_period: u64,           // Max spend per period
+    pub period_duration: i64,            // Period length in seconds
+    pub period_start: i64,               // Start of the current period
+    pub spent_in_period: u64,            // Spent in the current period
+    pub total_spent: u64,                // Lifetime spent
+    pub expires_at: i64,                 // Allowance expiry
+    pub delegate_bump: u8,               // Bump for the delegate PDA
+    pub bump: u8,                        // Bump seed
+}
+
+// Allowance Counter (one per token account, all allowances share its delegate PDA)
+// The SPL approval is dropped when the last allowance on the account is revoked.
+#[account]
+pub struct AllowanceCounter {
+    pub token_account: Pubkey,           // Owner's token account
+    pub open_allowances: u64,            // Allowances not revoked yet
+    pub bump: u8,                        // Bump seed
+}
+
+impl AllowanceCounter {
+    pub const LEN: usize = 8 + 32 + 8 + 1;
+}
+
+impl Allowance {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1;
+
+    // Debit the current period, rolling it over once it has elapsed
+    pub fn debit(&mut self, amount: u64, now: i64) -> Result<()> {
+        require!(now < self.expires_at, AllowanceError::AllowanceExpired);
+
+        if now >= self.period_start.saturating_add(self.period_duration) {
+            let periods = (now - self.period_start) / self.period_duration;
+            self.period_start += periods * self.period_duration;
+            self.spent_in_period = 0;
+        }
+
+        let spent_in_period = self
+            .spent_in_period
+            .checked_add(amount)
+            .ok_or(AllowanceError::PeriodLimitExceeded)?;
+        require!(
+            spent_in_period <= self.limit_per_period,
+            AllowanceError::PeriodLimitExceeded
+        );
+
+        self.spent_in_period = spent_in_period;
+        self.total_spent = self.total_spent.saturating_add(amount);
+        Ok(())
+    }
+}
+
+#[event]
+pub struct AllowanceGranted {
+    pub allowance: Pubkey,
+    pub owner: Pubkey,
+    pub token_account: Pubkey,
+    pub spender: Pubkey,
+    pub limit_per_period: u64,
+    pub period_duration: i64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AllowanceSpent {
+    pub allowance: Pubkey,
+    pub spender: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub spent_in_period: u64,
+    pub total_spent: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AllowanceRevoked {
+    pub allowance: Pubkey,
+    pub owner: Pubkey,
+    pub spender: Pubkey,
+    pub total_spent: u64,
+    pub open_allowances: u64,
+    pub timestamp: i64,
+}
+
+// Grant (or replace) a spending allowance
+pub(crate) fn grant(
+    ctx: Context<GrantAllowance>,
+    limit_per_period: u64,
+    period_duration: i64,
+    expires_at: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(period_duration > 0, AllowanceError::InvalidPeriod);
+    require!(
+        expires_at > clock.unix_timestamp,
+        AllowanceError::InvalidExpiry
+    );
+
+    // Make the program delegate the token account's SPL delegate
+    token_interface::approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Approve {
+                to: ctx.accounts.token_account.to_account_info(),
+                delegate: ctx.accounts.delegate.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        u64::MAX,
+    )?;
+
+    // A replaced grant keeps its slot in the counter
+    let counter = &mut ctx.accounts.counter;
+    if ctx.accounts.allowance.owner == Pubkey::default() {
+        counter.token_account = ctx.accounts.token_account.key();
+        counter.open_allowances += 1;
+        counter.bump = ctx.bumps.counter;
+    }
+
+    let allowance = &mut ctx.accounts.allowance;
+    allowance.owner = ctx.accounts.owner.key();
+    allowance.token_account = ctx.accounts.token_account.key();
+    allowance.mint = ctx.accounts.mint.key();
+    allowance.spender = ctx.accounts.spender.key();
+    allowance.limit_per_period = limit_per_period;
+    allowance.period_duration = period_duration;
+    allowance.period_start = clock.unix_timestamp;
+    allowance.spent_in_period = 0;
+    allowance.total_spent = 0;
+    allowance.expires_at = expires_at;
+    allowance.delegate_bump = ctx.bumps.delegate;
+    allowance.bump = ctx.bumps.allowance;
+
+    emit!(AllowanceGranted {
+        allowance: allowance.key(),
+        owner: allowance.owner,
+        token_account: allowance.token_account,
+        spender: allowance.spender,
+        limit_per_period,
+        period_duration,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Spend from an allowance (spender)
+pub(crate) fn spend(ctx: Context<SpendAllowance>, amount: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    ctx.accounts.allowance.debit(amount, clock.unix_timestamp)?;
+
+    let token_account_key = ctx.accounts.token_account.key();
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.delegate.to_account_info(),
+            },
+            &[&[
+                b"allowance-delegate",
+                token_account_key.as_ref(),
+                &[ctx.accounts.allowance.delegate_bump],
+            ]],
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let allowance = &ctx.accounts.allowance;
+    emit!(AllowanceSpent {
+        allowance: allowance.key(),
+        spender: allowance.spender,
+        destination: ctx.accounts.destination.key(),
+        amount,
+        spent_in_period: allowance.spent_in_period,
+        total_spent: allowance.total_spent,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Close an allowance (owner); rent goes back to the owner
+// Closing the last allowance on a token account also revokes the SPL delegate approval
+pub(crate) fn revoke(ctx: Context<RevokeAllowance>) -> Result<()> {
+    let counter = &mut ctx.accounts.counter;
+    counter.open_allowances = counter.open_allowances.saturating_sub(1);
+
+    // Leave approvals the owner has since given to someone else alone
+    let token_account = &ctx.accounts.token_account;
+    let still_delegated = token_account.owner == ctx.accounts.owner.key()
+        && token_account.delegate == Some(ctx.accounts.delegate.key()).into();
+    if counter.open_allowances == 0 && still_delegated {
+        token_interface::revoke(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Revoke {
+                source: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ))?;
+    }
+
+    let allowance = &ctx.accounts.allowance;
+    emit!(AllowanceRevoked {
+        allowance: allowance.key(),
+        owner: allowance.owner,
+        spender: allowance.spender,
+        total_spent: allowance.total_spent,
+        open_allowances: counter.open_allowances,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GrantAllowance<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Allowance::LEN,
+        seeds = [b"allowance", token_account.key().as_ref(), spender.key().as_ref()],
+        bump
+    )]
+    pub allowance: Account<'info, Allowance>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = AllowanceCounter::LEN,
+        seeds = [b"allowance-counter", token_account.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, AllowanceCounter>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA delegate for the token account, holds no data
+    #[account(seeds = [b"allowance-delegate", token_account.key().as_ref()], bump)]
+    pub delegate: UncheckedAccount<'info>,
+    /// CHECK: Only recorded as the spender
+    pub spender: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SpendAllowance<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        has_one = token_account,
+        has_one = spender,
+        seeds = [b"allowance", token_account.key().as_ref(), spender.key().as_ref()],
+        bump = allowance.bump
+    )]
+    pub allowance: Account<'info, Allowance>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, constraint = token_account.owner == allowance.owner @ AllowanceError::OwnerChanged)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA delegate for the token account, holds no data
+    #[account(
+        seeds = [b"allowance-delegate", token_account.key().as_ref()],
+        bump = allowance.delegate_bump
+    )]
+    pub delegate: UncheckedAccount<'info>,
+    pub spender: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAllowance<'info> {
+    #[account(mut, close = owner, has_one = owner, has_one = token_account)]
+    pub allowance: Account<'info, Allowance>,
+    #[account(
+        mut,
+        seeds = [b"allowance-counter", token_account.key().as_ref()],
+        bump = counter.bump
+    )]
+    pub counter: Account<'info, AllowanceCounter>,
+    #[account(mut)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA delegate for the token account, holds no data
+    #[account(
+        seeds = [b"allowance-delegate", token_account.key().as_ref()],
+        bump = allowance.delegate_bump
+    )]
+    pub delegate: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[error_code]
+pub enum AllowanceError {
+    #[msg("Period duration must be positive")]
+    InvalidPeriod,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Allowance has expired")]
+    AllowanceExpired,
+    #[msg("Spend exceeds the allowance for this period")]
+    PeriodLimitExceeded,
+    #[msg("Token account owner changed since the grant")]
+    OwnerChanged,
+}
@@ -0,0 +1,225 @@
+use anchor_lang::prelude::*;
+
+use crate::config::TokenConfig;
+use crate::mining::MiningVault;
+use crate::staking::StakingPool;
+
+// Accounts whose admin authority can be handed over in two steps:
+// the current authority proposes, the new key accepts by signing.
+// Renouncing sets the authority to the default pubkey, which can never sign.
+pub trait TransferableAuthority {
+    fn authority(&self) -> Pubkey;
+    fn pending_authority(&self) -> Option<Pubkey>;
+    fn set_authority(&mut self, authority: Pubkey);
+    fn set_pending_authority(&mut self, pending_authority: Option<Pubkey>);
+}
+
+impl TransferableAuthority for StakingPool {
+    fn authority(&self) -> Pubkey {
+        self.pool_authority
+    }
+    fn pending_authority(&self) -> Option<Pubkey> {
+        self.pending_authority
+    }
+    fn set_authority(&mut self, authority: Pubkey) {
+        self.pool_authority = authority;
+    }
+    fn set_pending_authority(&mut self, pending_authority: Option<Pubkey>) {
+        self.pending_authority = pending_authority;
+    }
+}
+
+impl TransferableAuthority for MiningVault {
+    fn authority(&self) -> Pubkey {
+        self.pool_authority
+    }
+    fn pending_authority(&self) -> Option<Pubkey> {
+        self.pending_authority
+    }
+    fn set_authority(&mut self, authority: Pubkey) {
+        self.pool_authority = authority;
+    }
+    fn set_pending_authority(&mut self, pending_authority: Option<Pubkey>) {
+        self.pending_authority = pending_authority;
+    }
+}
+
+impl TransferableAuthority for TokenConfig {
+    fn authority(&self) -> Pubkey {
+        self.authority
+    }
+    fn pending_authority(&self) -> Option<Pubkey> {
+        self.pending_authority
+    }
+    fn set_authority(&mut self, authority: Pubkey) {
+        self.authority = authority;
+    }
+    fn set_pending_authority(&mut self, pending_authority: Option<Pubkey>) {
+        self.pending_authority = pending_authority;
+    }
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub account: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityAccepted {
+    pub account: Pubkey,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityRenounced {
+    pub account: Pubkey,
+    pub previous_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+// Propose a new authority (current authority), None cancels a pending proposal
+pub(crate) fn propose<'info, T>(
+    account: &mut Account<'info, T>,
+    new_authority: Option<Pubkey>,
+) -> Result<()>
+where
+    T: TransferableAuthority + AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    if let Some(new_authority) = new_authority {
+        require!(
+            new_authority != Pubkey::default() && new_authority != account.authority(),
+            AuthorityError::InvalidNewAuthority
+        );
+    }
+    account.set_pending_authority(new_authority);
+
+    emit!(AuthorityProposed {
+        account: account.key(),
+        authority: account.authority(),
+        pending_authority: new_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Accept a proposed authority (pending authority)
+pub(crate) fn accept<'info, T>(account: &mut Account<'info, T>, signer: Pubkey) -> Result<()>
+where
+    T: TransferableAuthority + AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    require!(
+        account.pending_authority() == Some(signer),
+        AuthorityError::NotPendingAuthority
+    );
+
+    let previous_authority = account.authority();
+    account.set_authority(signer);
+    account.set_pending_authority(None);
+
+    emit!(AuthorityAccepted {
+        account: account.key(),
+        previous_authority,
+        authority: signer,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Give up authority for good (current authority)
+pub(crate) fn renounce<'info, T>(account: &mut Account<'info, T>) -> Result<()>
+where
+    T: TransferableAuthority + AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    let previous_authority = account.authority();
+    account.set_authority(Pubkey::default());
+    account.set_pending_authority(None);
+
+    emit!(AuthorityRenounced {
+        account: account.key(),
+        previous_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetStakingPoolAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking-pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = pool_authority
+    )]
+    pub pool: Account<'info, StakingPool>,
+    pub pool_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptStakingPoolAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking-pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, StakingPool>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMiningVaultAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"mining-vault", vault.mint.as_ref()],
+        bump = vault.bump,
+        has_one = pool_authority
+    )]
+    pub vault: Account<'info, MiningVault>,
+    pub pool_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptMiningVaultAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"mining-vault", vault.mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, MiningVault>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTokenAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"token-config", config.mint.as_ref()],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Account<'info, TokenConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptTokenAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"token-config", config.mint.as_ref()],
+        bump = config.bump
+    )]
+    pub config: Account<'info, TokenConfig>,
+    pub new_authority: Signer<'info>,
+}
+
+#[error_code]
+pub enum AuthorityError {
+    #[msg("New authority must differ from the current one and not be the default pubkey")]
+    InvalidNewAuthority,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+
+// Burn Reason Codes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BurnReason {
+    User,      // Voluntary burn by a holder
+    Fee,       // Protocol fee burn
+    Buyback,   // Treasury buyback burn
+}
+
+// Global Burn Ledger (per mint)
+#[account]
+pub struct BurnLedger {
+    pub mint: Pubkey,                    // JDH Token mint
+    pub total_burned: u64,               // All burns
+    pub user_burned: u64,                // BurnReason::User
+    pub fee_burned: u64,                 // BurnReason::Fee
+    pub buyback_burned: u64,             // BurnReason::Buyback
+    pub burn_count: u64,                 // Number of burns
+    pub bump: u8,                        // Bump seed
+}
+
+// Per-user Burn Record
+#[account]
+pub struct UserBurnRecord {
+    pub user: Pubkey,                    // Burning wallet
+    pub mint: Pubkey,                    // JDH Token mint
+    pub total_burned: u64,               // All burns
+    pub user_burned: u64,                // BurnReason::User
+    pub fee_burned: u64,                 // BurnReason::Fee
+    pub buyback_burned: u64,             // BurnReason::Buyback
+    pub last_burn_at: i64,               // Last burn timestamp
+    pub bump: u8,                        // Bump seed
+}
+
+impl BurnLedger {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    pub fn record(&mut self, amount: u64, reason: BurnReason) -> Result<()> {
+        self.total_burned = self
+            .total_burned
+            .checked_add(amount)
+            .ok_or(BurnError::AmountOverflow)?;
+        let bucket = match reason {
+            BurnReason::User => &mut self.user_burned,
+            BurnReason::Fee => &mut self.fee_burned,
+            BurnReason::Buyback => &mut self.buyback_burned,
+        };
+        *bucket = bucket.checked_add(amount).ok_or(BurnError::AmountOverflow)?;
+        self.burn_count += 1;
+        Ok(())
+    }
+}
+
+impl UserBurnRecord {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    pub fn record(&mut self, amount: u64, reason: BurnReason, now: i64) -> Result<()> {
+        self.total_burned = self
+            .total_burned
+            .checked_add(amount)
+            .ok_or(BurnError::AmountOverflow)?;
+        let bucket = match reason {
+            BurnReason::User => &mut self.user_burned,
+            BurnReason::Fee => &mut self.fee_burned,
+            BurnReason::Buyback => &mut self.buyback_burned,
+        };
+        *bucket = bucket.checked_add(amount).ok_or(BurnError::AmountOverflow)?;
+        self.last_burn_at = now;
+        Ok(())
+    }
+}
+
+#[event]
+pub struct TokensBurned {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub from: Pubkey,
+    pub amount: u64,
+    pub reason: BurnReason,
+    pub user_total_burned: u64,
+    pub total_burned: u64,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum BurnError {
+    #[msg("Only the token authority can record fee or buyback burns")]
+    UnauthorizedBurnReason,
+    #[msg("Amount overflow")]
+    AmountOverflow,
+}
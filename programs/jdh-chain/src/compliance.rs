@@ -0,0 +1,264 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, FreezeAccount, Mint, ThawAccount, TokenAccount, TokenInterface,
+};
+
+use crate::config::TokenConfig;
+
+// Freeze Counter Account (one per token account, indexes its freeze records)
+#[account]
+pub struct FreezeCounter {
+    pub token_account: Pubkey,           // Token account
+    pub freeze_count: u64,               // Times frozen, records are 0..freeze_count
+    pub bump: u8,                        // Bump seed
+}
+
+impl FreezeCounter {
+    pub const LEN: usize = 8 + 32 + 8 + 1;
+}
+
+// Freeze Record Account (one per freeze, completed by the thaw that lifts it)
+#[account]
+pub struct FreezeRecord {
+    pub token_account: Pubkey,           // Frozen token account
+    pub owner: Pubkey,                   // Token account owner
+    pub mint: Pubkey,                    // JDH Token mint
+    pub index: u64,                      // Position in the account's freeze history
+    pub freeze_reason: u16,              // Reason code given for the freeze
+    pub frozen_by: Pubkey,               // Compliance signer of the freeze
+    pub frozen_at: i64,                  // Timestamp of the freeze
+    pub thaw_reason: u16,                // Reason code given for the thaw (0 while frozen)
+    pub thawed_by: Pubkey,               // Compliance signer of the thaw
+    pub thawed_at: i64,                  // Timestamp of the thaw (0 while frozen)
+    pub bump: u8,                        // Bump seed
+}
+
+impl FreezeRecord {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 2 + 32 + 8 + 2 + 32 + 8 + 1;
+}
+
+#[event]
+pub struct AccountFrozen {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub reason_code: u16,
+    pub actor: Pubkey,
+    pub freeze_count: u64,
+    pub record: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountThawed {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub reason_code: u16,
+    pub actor: Pubkey,
+    pub record: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ComplianceAuthorityUpdated {
+    pub mint: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+// Freeze Token Account
+pub fn freeze(ctx: Context<FreezeTokenAccount>, reason_code: u16) -> Result<()> {
+    require!(reason_code != 0, ComplianceError::MissingReasonCode);
+
+    let mint_key = ctx.accounts.mint.key();
+    token_interface::freeze_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        FreezeAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.freeze_authority.to_account_info(),
+        },
+        &[&[
+            b"freeze-authority",
+            mint_key.as_ref(),
+            &[ctx.accounts.config.freeze_authority_bump],
+        ]],
+    ))?;
+
+    let counter = &mut ctx.accounts.freeze_counter;
+    counter.token_account = ctx.accounts.token_account.key();
+    counter.bump = ctx.bumps.freeze_counter;
+
+    let record = &mut ctx.accounts.freeze_record;
+    record.token_account = counter.token_account;
+    record.owner = ctx.accounts.token_account.owner;
+    record.mint = mint_key;
+    record.index = counter.freeze_count;
+    record.freeze_reason = reason_code;
+    record.frozen_by = ctx.accounts.compliance_authority.key();
+    record.frozen_at = Clock::get()?.unix_timestamp;
+    record.bump = ctx.bumps.freeze_record;
+
+    counter.freeze_count += 1;
+
+    emit!(AccountFrozen {
+        token_account: record.token_account,
+        owner: record.owner,
+        mint: record.mint,
+        reason_code,
+        actor: record.frozen_by,
+        freeze_count: counter.freeze_count,
+        record: record.key(),
+        timestamp: record.frozen_at,
+    });
+    Ok(())
+}
+
+// Thaw Token Account
+pub fn thaw(ctx: Context<ThawTokenAccount>, reason_code: u16) -> Result<()> {
+    require!(reason_code != 0, ComplianceError::MissingReasonCode);
+
+    let mint_key = ctx.accounts.mint.key();
+    token_interface::thaw_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        ThawAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.freeze_authority.to_account_info(),
+        },
+        &[&[
+            b"freeze-authority",
+            mint_key.as_ref(),
+            &[ctx.accounts.config.freeze_authority_bump],
+        ]],
+    ))?;
+
+    let record = &mut ctx.accounts.freeze_record;
+    record.thaw_reason = reason_code;
+    record.thawed_by = ctx.accounts.compliance_authority.key();
+    record.thawed_at = Clock::get()?.unix_timestamp;
+
+    emit!(AccountThawed {
+        token_account: record.token_account,
+        owner: record.owner,
+        mint: record.mint,
+        reason_code,
+        actor: record.thawed_by,
+        record: record.key(),
+        timestamp: record.thawed_at,
+    });
+    Ok(())
+}
+
+// Set Compliance Authority
+pub fn update_compliance_authority(
+    ctx: Context<SetComplianceAuthority>,
+    compliance_authority: Pubkey,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let old_authority = config.compliance_authority;
+    config.compliance_authority = compliance_authority;
+
+    emit!(ComplianceAuthorityUpdated {
+        mint: config.mint,
+        old_authority,
+        new_authority: compliance_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FreezeTokenAccount<'info> {
+    #[account(
+        seeds = [b"token-config", mint.key().as_ref()],
+        bump = config.bump,
+        has_one = mint,
+        has_one = compliance_authority
+    )]
+    pub config: Account<'info, TokenConfig>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = compliance_authority,
+        space = FreezeCounter::LEN,
+        seeds = [b"freeze-counter", token_account.key().as_ref()],
+        bump
+    )]
+    pub freeze_counter: Account<'info, FreezeCounter>,
+    #[account(
+        init,
+        payer = compliance_authority,
+        space = FreezeRecord::LEN,
+        seeds = [
+            b"freeze-record",
+            token_account.key().as_ref(),
+            freeze_counter.freeze_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+    /// CHECK: PDA signer for freezing, holds no data
+    #[account(
+        seeds = [b"freeze-authority", mint.key().as_ref()],
+        bump = config.freeze_authority_bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub compliance_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ThawTokenAccount<'info> {
+    #[account(
+        seeds = [b"token-config", mint.key().as_ref()],
+        bump = config.bump,
+        has_one = mint,
+        has_one = compliance_authority
+    )]
+    pub config: Account<'info, TokenConfig>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    // The open record of the freeze being lifted
+    #[account(
+        mut,
+        seeds = [
+            b"freeze-record",
+            token_account.key().as_ref(),
+            freeze_record.index.to_le_bytes().as_ref()
+        ],
+        bump = freeze_record.bump,
+        constraint = freeze_record.thawed_at == 0 @ ComplianceError::FreezeAlreadyLifted
+    )]
+    pub freeze_record: Account<'info, FreezeRecord>,
+    /// CHECK: PDA signer for freezing, holds no data
+    #[account(
+        seeds = [b"freeze-authority", mint.key().as_ref()],
+        bump = config.freeze_authority_bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    pub compliance_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetComplianceAuthority<'info> {
+    #[account(mut, has_one = authority)]
+    pub config: Account<'info, TokenConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[error_code]
+pub enum ComplianceError {
+    #[msg("A non-zero reason code is required")]
+    MissingReasonCode,
+    #[msg("Freeze was already lifted by a thaw")]
+    FreezeAlreadyLifted,
+}
@@ -0,0 +1,300 @@
+use anchor_lang::prelude::*;
+
+// JDH Token Config Account
+#[account]
+pub struct TokenConfig {
+    pub mint: Pubkey,                    // JDH Token mint
+    pub authority: Pubkey,               // Admin authority (default = renounced)
+    pub pending_authority: Option<Pubkey>, // Proposed authority, must accept
+    pub compliance_authority: Pubkey,    // May freeze and thaw token accounts
+    pub decimals: u8,                    // Mint decimals
+    pub mint_authority_bump: u8,         // Bump for mint authority PDA
+    pub freeze_authority_bump: u8,       // Bump for freeze authority PDA
+    pub max_supply: u64,                 // Hard cap on total tokens ever minted
+    pub total_minted: u64,               // Total tokens minted so far
+    pub emission_start: i64,             // Start of epoch 0
+    pub epoch_duration: i64,             // Epoch length in seconds
+    pub initial_epoch_emission: u64,     // Mint budget for epoch 0
+    pub emission_decay_bps: u16,         // Budget decay per epoch in basis points
+    pub current_epoch: u64,              // Epoch of the last mint
+    pub epoch_minted: u64,               // Minted during current_epoch
+    pub bump: u8,                        // Bump seed
+}
+
+impl TokenConfig {
+    pub const LEN: usize = 8 + 32 + 32 + (1 + 32) + 32 + 1 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 2 + 8 + 8 + 1;
+
+    // Mint budget for an epoch: initial_epoch_emission * (1 - decay)^epoch
+    pub fn epoch_budget(&self, epoch: u64) -> u64 {
+        const SCALE: u128 = 1_000_000_000_000;
+        let mut factor = SCALE;
+        let mut base = (10_000 - self.emission_decay_bps as u128) * SCALE / 10_000;
+        let mut exp = epoch;
+
+        while exp > 0 && factor > 0 {
+            if exp & 1 == 1 {
+                factor = factor * base / SCALE;
+            }
+            base = base * base / SCALE;
+            exp >>= 1;
+        }
+
+        (self.initial_epoch_emission as u128 * factor / SCALE) as u64
+    }
+
+    // Check an amount against the supply cap and epoch budget, then record it
+    pub fn record_emission(&mut self, amount: u64, now: i64) -> Result<()> {
+        let total_minted = self
+            .total_minted
+            .checked_add(amount)
+            .ok_or(TokenError::MaxSupplyExceeded)?;
+        require!(
+            total_minted <= self.max_supply,
+            TokenError::MaxSupplyExceeded
+        );
+
+        let elapsed = now.saturating_sub(self.emission_start).max(0);
+        let epoch = (elapsed / self.epoch_duration) as u64;
+        if epoch != self.current_epoch {
+            self.current_epoch = epoch;
+            self.epoch_minted = 0;
+        }
+
+        let epoch_minted = self
+            .epoch_minted
+            .checked_add(amount)
+            .ok_or(TokenError::EpochEmissionExceeded)?;
+        require!(
+            epoch_minted <= self.epoch_budget(epoch),
+            TokenError::EpochEmissionExceeded
+        );
+
+        self.total_minted = total_minted;
+        self.epoch_minted = epoch_minted;
+        Ok(())
+    }
+}
+
+// Token-2022 transfer fee set on the mint at creation, withheld by the token program itself
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct TransferFeeParams {
+    pub transfer_fee_bps: u16,           // Fee rate in basis points
+    pub maximum_fee: u64,                // Fee cap per transfer
+}
+
+// Program-wide Config Account (emergency pause switches)
+#[account]
+pub struct ProgramConfig {
+    pub admin: Pubkey,                   // Can pause, unpause and set the guardian
+    pub guardian: Pubkey,                // Can pause instantly
+    pub token_ops_paused: bool,          // Mint, transfer, burn, vesting, airdrop
+    pub staking_paused: bool,            // Staking pool operations
+    pub governance_paused: bool,         // Proposals and votes
+    pub mining_paused: bool,             // Mining vault operations
+    pub bump: u8,                        // Bump seed
+}
+
+// Pausable Subsystem
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Subsystem {
+    TokenOps,
+    Staking,
+    Governance,
+    Mining,
+}
+
+impl ProgramConfig {
+    pub const LEN: usize = 8 + 32 + 32 + 1 + 1 + 1 + 1 + 1;
+
+    fn set_paused(&mut self, subsystem: Subsystem, paused: bool) {
+        match subsystem {
+            Subsystem::TokenOps => self.token_ops_paused = paused,
+            Subsystem::Staking => self.staking_paused = paused,
+            Subsystem::Governance => self.governance_paused = paused,
+            Subsystem::Mining => self.mining_paused = paused,
+        }
+    }
+}
+
+#[event]
+pub struct MintInitialized {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub token_program: Pubkey,
+    pub decimals: u8,
+    pub max_supply: u64,
+    pub epoch_duration: i64,
+    pub initial_epoch_emission: u64,
+    pub emission_decay_bps: u16,
+    pub transfer_fee: Option<TransferFeeParams>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProgramConfigInitialized {
+    pub admin: Pubkey,
+    pub guardian: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubsystemPaused {
+    pub subsystem: Subsystem,
+    pub by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubsystemUnpaused {
+    pub subsystem: Subsystem,
+    pub by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuardianUpdated {
+    pub old_guardian: Pubkey,
+    pub new_guardian: Pubkey,
+    pub timestamp: i64,
+}
+
+// Initialize Program Config (upgrade authority only)
+pub fn initialize_program(ctx: Context<InitializeProgramConfig>, guardian: Pubkey) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+    program_config.admin = ctx.accounts.admin.key();
+    program_config.guardian = guardian;
+    program_config.token_ops_paused = false;
+    program_config.staking_paused = false;
+    program_config.governance_paused = false;
+    program_config.mining_paused = false;
+    program_config.bump = ctx.bumps.program_config;
+
+    emit!(ProgramConfigInitialized {
+        admin: program_config.admin,
+        guardian,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Pause a subsystem (guardian or admin)
+pub fn pause_subsystem(ctx: Context<SetPause>, subsystem: Subsystem) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+    let signer = ctx.accounts.signer.key();
+
+    require!(
+        signer == program_config.guardian || signer == program_config.admin,
+        PauseError::Unauthorized
+    );
+
+    program_config.set_paused(subsystem, true);
+
+    emit!(SubsystemPaused {
+        subsystem,
+        by: signer,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Unpause a subsystem (admin only)
+pub fn unpause_subsystem(ctx: Context<SetPause>, subsystem: Subsystem) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+
+    require_keys_eq!(
+        ctx.accounts.signer.key(),
+        program_config.admin,
+        PauseError::Unauthorized
+    );
+
+    program_config.set_paused(subsystem, false);
+
+    emit!(SubsystemUnpaused {
+        subsystem,
+        by: ctx.accounts.signer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Replace the guardian (admin only)
+pub fn update_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+    let old_guardian = program_config.guardian;
+    program_config.guardian = guardian;
+
+    emit!(GuardianUpdated {
+        old_guardian,
+        new_guardian: guardian,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeProgramConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = ProgramConfig::LEN,
+        seeds = [b"program-config"],
+        bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::JdhChain>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ PauseError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"program-config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(
+        mut,
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        has_one = admin
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[error_code]
+pub enum PauseError {
+    #[msg("Signer is not allowed to change pause state")]
+    Unauthorized,
+    #[msg("Token operations are paused")]
+    TokenOpsPaused,
+    #[msg("Staking is paused")]
+    StakingPaused,
+    #[msg("Governance is paused")]
+    GovernancePaused,
+    #[msg("Mining is paused")]
+    MiningPaused,
+}
+
+#[error_code]
+pub enum TokenError {
+    #[msg("Invalid emission schedule")]
+    InvalidEmissionSchedule,
+    #[msg("Mint would exceed max supply")]
+    MaxSupplyExceeded,
+    #[msg("Mint would exceed the current epoch's emission budget")]
+    EpochEmissionExceeded,
+    #[msg("Transfer fees need a Token-2022 mint and at most 10000 basis points")]
+    InvalidTransferFee,
+}
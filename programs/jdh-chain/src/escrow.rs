@@ -0,0 +1,402 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::config::{PauseError, ProgramConfig};
+use crate::transfer::{harvest_withheld, transfer_in};
+
+// Escrow Status
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EscrowStatus {
+    Active,     // Funds locked
+    Disputed,   // Only the arbiter may settle
+}
+
+// Escrow Account (tokens held in a PDA vault per escrow)
+#[account]
+pub struct Escrow {
+    pub id: u64,                         // Escrow ID (per sender)
+    pub mint: Pubkey,                    // JDH Token mint
+    pub sender: Pubkey,                  // Funder, receives refunds and rent
+    pub recipient: Pubkey,               // Receives released funds
+    pub arbiter: Option<Pubkey>,         // Optional dispute resolver
+    pub release_time: Option<i64>,       // Recipient may self-release after this
+    pub vault: Pubkey,                   // PDA token account holding the funds
+    pub amount: u64,                     // Escrowed amount
+    pub status: EscrowStatus,            // Current status
+    pub created_at: i64,                 // Creation timestamp
+    pub bump: u8,                        // Bump seed
+}
+
+impl Escrow {
+    pub const LEN: usize = 8 + 8 + 32 + 32 + 32 + (1 + 32) + (1 + 8) + 32 + 8 + 1 + 8 + 1;
+
+    fn is_arbiter(&self, key: &Pubkey) -> bool {
+        self.arbiter.as_ref() == Some(key)
+    }
+}
+
+#[event]
+pub struct EscrowCreated {
+    pub escrow: Pubkey,
+    pub id: u64,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub arbiter: Option<Pubkey>,
+    pub release_time: Option<i64>,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowReleased {
+    pub escrow: Pubkey,
+    pub id: u64,
+    pub recipient: Pubkey,
+    pub released_by: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowRefunded {
+    pub escrow: Pubkey,
+    pub id: u64,
+    pub sender: Pubkey,
+    pub refunded_by: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowDisputed {
+    pub escrow: Pubkey,
+    pub id: u64,
+    pub disputed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+// Create Escrow and lock tokens in its vault
+pub(crate) fn create(
+    ctx: Context<CreateEscrow>,
+    id: u64,
+    amount: u64,
+    arbiter: Option<Pubkey>,
+    release_time: Option<i64>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(amount > 0, EscrowError::InvalidAmount);
+    if let Some(release_time) = release_time {
+        require!(
+            release_time > clock.unix_timestamp,
+            EscrowError::InvalidReleaseTime
+        );
+    }
+
+    let received = transfer_in(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.sender_token_account.to_account_info(),
+        &mut ctx.accounts.vault,
+        ctx.accounts.sender.to_account_info(),
+        &[],
+        amount,
+    )?;
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.id = id;
+    escrow.mint = ctx.accounts.mint.key();
+    escrow.sender = ctx.accounts.sender.key();
+    escrow.recipient = ctx.accounts.recipient.key();
+    escrow.arbiter = arbiter;
+    escrow.release_time = release_time;
+    escrow.vault = ctx.accounts.vault.key();
+    escrow.amount = received;
+    escrow.status = EscrowStatus::Active;
+    escrow.created_at = clock.unix_timestamp;
+    escrow.bump = ctx.bumps.escrow;
+
+    emit!(EscrowCreated {
+        escrow: escrow.key(),
+        id,
+        sender: escrow.sender,
+        recipient: escrow.recipient,
+        arbiter,
+        release_time,
+        amount: escrow.amount,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Release Escrow to the recipient
+// Sender or arbiter at any time, recipient once release_time passed and not disputed
+pub(crate) fn release(ctx: Context<ReleaseEscrow>) -> Result<()> {
+    let clock = Clock::get()?;
+    let escrow = &ctx.accounts.escrow;
+    let signer = ctx.accounts.signer.key();
+
+    let recipient_can_release = signer == escrow.recipient
+        && escrow.status == EscrowStatus::Active
+        && escrow
+            .release_time
+            .is_some_and(|release_time| clock.unix_timestamp >= release_time);
+    require!(
+        signer == escrow.sender || escrow.is_arbiter(&signer) || recipient_can_release,
+        EscrowError::Unauthorized
+    );
+
+    let amount = ctx.accounts.vault.amount;
+    settle(
+        escrow,
+        &ctx.accounts.vault,
+        &ctx.accounts.mint,
+        ctx.accounts.recipient_token_account.to_account_info(),
+        ctx.accounts.sender.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    emit!(EscrowReleased {
+        escrow: escrow.key(),
+        id: escrow.id,
+        recipient: escrow.recipient,
+        released_by: signer,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Refund Escrow to the sender (recipient or arbiter)
+pub(crate) fn refund(ctx: Context<RefundEscrow>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+    let signer = ctx.accounts.signer.key();
+
+    require!(
+        signer == escrow.recipient || escrow.is_arbiter(&signer),
+        EscrowError::Unauthorized
+    );
+
+    let amount = ctx.accounts.vault.amount;
+    settle(
+        escrow,
+        &ctx.accounts.vault,
+        &ctx.accounts.mint,
+        ctx.accounts.sender_token_account.to_account_info(),
+        ctx.accounts.sender.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    emit!(EscrowRefunded {
+        escrow: escrow.key(),
+        id: escrow.id,
+        sender: escrow.sender,
+        refunded_by: signer,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Dispute Escrow (sender or recipient), leaves settlement to the arbiter
+pub(crate) fn dispute(ctx: Context<DisputeEscrow>) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+    let signer = ctx.accounts.signer.key();
+
+    require!(escrow.arbiter.is_some(), EscrowError::NoArbiter);
+    require!(
+        escrow.status == EscrowStatus::Active,
+        EscrowError::AlreadyDisputed
+    );
+    require!(
+        signer == escrow.sender || signer == escrow.recipient,
+        EscrowError::Unauthorized
+    );
+
+    escrow.status = EscrowStatus::Disputed;
+
+    emit!(EscrowDisputed {
+        escrow: escrow.key(),
+        id: escrow.id,
+        disputed_by: signer,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Pay out the whole vault and close it, rent goes back to the sender
+fn settle<'info>(
+    escrow: &Account<'info, Escrow>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: AccountInfo<'info>,
+    sender: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    let id_bytes = escrow.id.to_le_bytes();
+    let seeds: &[&[&[u8]]] = &[&[
+        b"escrow",
+        escrow.sender.as_ref(),
+        id_bytes.as_ref(),
+        &[escrow.bump],
+    ]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TransferChecked {
+                from: vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: destination,
+                authority: escrow.to_account_info(),
+            },
+            seeds,
+        ),
+        vault.amount,
+        mint.decimals,
+    )?;
+
+    // Fees withheld on the deposit would otherwise block the close
+    harvest_withheld(
+        token_program.clone(),
+        mint.to_account_info(),
+        vault.to_account_info(),
+    )?;
+
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program,
+        CloseAccount {
+            account: vault.to_account_info(),
+            destination: sender,
+            authority: escrow.to_account_info(),
+        },
+        seeds,
+    ))
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateEscrow<'info> {
+    #[account(
+        init,
+        payer = sender,
+        space = Escrow::LEN,
+        seeds = [b"escrow", sender.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(
+        init,
+        payer = sender,
+        seeds = [b"escrow-vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: Only recorded as the recipient
+    pub recipient: UncheckedAccount<'info>,
+    #[account(mut, token::mint = mint, token::authority = sender)]
+    pub sender_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseEscrow<'info> {
+    #[account(
+        mut,
+        close = sender,
+        has_one = mint,
+        has_one = sender,
+        has_one = vault
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    // Receives fees withheld in the vault when it is closed
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = escrow.recipient)]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Escrow sender, receives the rent
+    #[account(mut)]
+    pub sender: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct RefundEscrow<'info> {
+    #[account(
+        mut,
+        close = sender,
+        has_one = mint,
+        has_one = sender,
+        has_one = vault
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    // Receives fees withheld in the vault when it is closed
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = escrow.sender)]
+    pub sender_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Escrow sender, receives the rent
+    #[account(mut)]
+    pub sender: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct DisputeEscrow<'info> {
+    #[account(mut)]
+    pub escrow: Account<'info, Escrow>,
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("Escrow amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Release time must be in the future")]
+    InvalidReleaseTime,
+    #[msg("Signer is not allowed to settle this escrow")]
+    Unauthorized,
+    #[msg("Escrow has no arbiter")]
+    NoArbiter,
+    #[msg("Escrow is already disputed")]
+    AlreadyDisputed,
+}
@@ -0,0 +1,251 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::config::TokenConfig;
+
+// Hard ceiling on the protocol fee rate (10%)
+pub const MAX_TRANSFER_FEE_BPS: u16 = 1_000;
+
+// Exchanges, program vaults and other owners that transfer without a fee
+pub const MAX_FEE_EXEMPTIONS: usize = 16;
+
+// Transfer Fee Config Account (one per mint, charged by transfer_tokens)
+#[account]
+pub struct FeeConfig {
+    pub mint: Pubkey,                    // JDH Token mint
+    pub treasury: Pubkey,                // Token account receiving fees
+    pub transfer_fee_bps: u16,           // Fee rate in basis points (0 = disabled)
+    pub max_fee: u64,                    // Fee cap per transfer
+    pub total_fees_collected: u64,       // Lifetime fees routed to the treasury
+    pub bump: u8,                        // Bump seed
+    pub exempt: Vec<Pubkey>,             // Token account owners that skip the fee
+}
+
+impl FeeConfig {
+    pub const LEN: usize = 8 + 32 + 32 + 2 + 8 + 8 + 1 + 4 + 32 * MAX_FEE_EXEMPTIONS;
+
+    pub fn is_exempt(&self, owner: &Pubkey) -> bool {
+        self.exempt.contains(owner)
+    }
+
+    // Fee owed on a transfer between two token account owners
+    pub fn fee_for(&self, amount: u64, from_owner: &Pubkey, to_owner: &Pubkey) -> u64 {
+        if self.transfer_fee_bps == 0 || self.is_exempt(from_owner) || self.is_exempt(to_owner) {
+            return 0;
+        }
+        let fee = amount as u128 * self.transfer_fee_bps as u128 / 10_000;
+        (fee as u64).min(self.max_fee)
+    }
+}
+
+// Fee config at the mint's fee-config PDA. Transfers always pass the PDA, so the fee cannot be
+// skipped by leaving it out. It holds no data until initialize_fee_config runs; from then on
+// the configured treasury must be passed with it.
+pub fn load_config(
+    fee_config: &AccountInfo,
+    treasury: Option<&InterfaceAccount<TokenAccount>>,
+) -> Result<Option<FeeConfig>> {
+    if fee_config.owner != &crate::ID {
+        return Ok(None);
+    }
+    let config = FeeConfig::try_deserialize(&mut &fee_config.try_borrow_data()?[..])?;
+    let treasury = treasury.ok_or(FeeError::MissingTreasury)?;
+    require_keys_eq!(treasury.key(), config.treasury, FeeError::InvalidTreasury);
+    Ok(Some(config))
+}
+
+// Add collected fees to the lifetime total and write the fee config back to its account
+pub fn record_collected(fee_config: &AccountInfo, config: &mut FeeConfig, fee: u64) -> Result<()> {
+    config.total_fees_collected = config.total_fees_collected.saturating_add(fee);
+    let mut data = fee_config.try_borrow_mut_data()?;
+    config.try_serialize(&mut &mut data[..])
+}
+
+// Fee owed once the mint has a fee config, none before
+pub fn fee_owed(
+    fee_config: Option<&FeeConfig>,
+    amount: u64,
+    from_owner: &Pubkey,
+    to_owner: &Pubkey,
+) -> u64 {
+    fee_config.map_or(0, |fee_config| fee_config.fee_for(amount, from_owner, to_owner))
+}
+
+#[event]
+pub struct FeeConfigUpdated {
+    pub mint: Pubkey,
+    pub treasury: Pubkey,
+    pub transfer_fee_bps: u16,
+    pub max_fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeExemptionSet {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub exempt: bool,
+    pub timestamp: i64,
+}
+
+// Initialize Fee Config (token authority)
+pub(crate) fn initialize(
+    ctx: Context<InitializeFeeConfig>,
+    transfer_fee_bps: u16,
+    max_fee: u64,
+) -> Result<()> {
+    require!(
+        transfer_fee_bps <= MAX_TRANSFER_FEE_BPS,
+        FeeError::FeeRateTooHigh
+    );
+
+    let fee_config = &mut ctx.accounts.fee_config;
+    fee_config.mint = ctx.accounts.mint.key();
+    fee_config.treasury = ctx.accounts.treasury.key();
+    fee_config.transfer_fee_bps = transfer_fee_bps;
+    fee_config.max_fee = max_fee;
+    fee_config.total_fees_collected = 0;
+    fee_config.bump = ctx.bumps.fee_config;
+    fee_config.exempt = Vec::new();
+
+    emit!(FeeConfigUpdated {
+        mint: fee_config.mint,
+        treasury: fee_config.treasury,
+        transfer_fee_bps,
+        max_fee,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Update the fee rate, cap and treasury (token authority)
+pub(crate) fn update(
+    ctx: Context<UpdateFeeConfig>,
+    transfer_fee_bps: u16,
+    max_fee: u64,
+) -> Result<()> {
+    require!(
+        transfer_fee_bps <= MAX_TRANSFER_FEE_BPS,
+        FeeError::FeeRateTooHigh
+    );
+
+    let fee_config = &mut ctx.accounts.fee_config;
+    fee_config.treasury = ctx.accounts.treasury.key();
+    fee_config.transfer_fee_bps = transfer_fee_bps;
+    fee_config.max_fee = max_fee;
+
+    emit!(FeeConfigUpdated {
+        mint: fee_config.mint,
+        treasury: fee_config.treasury,
+        transfer_fee_bps,
+        max_fee,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Add or remove a fee exemption for a token account owner (token authority)
+pub(crate) fn set_exemption(ctx: Context<SetFeeExemption>, owner: Pubkey, exempt: bool) -> Result<()> {
+    let fee_config = &mut ctx.accounts.fee_config;
+
+    if exempt {
+        require!(!fee_config.is_exempt(&owner), FeeError::AlreadyExempt);
+        require!(
+            fee_config.exempt.len() < MAX_FEE_EXEMPTIONS,
+            FeeError::TooManyExemptions
+        );
+        fee_config.exempt.push(owner);
+    } else {
+        let position = fee_config
+            .exempt
+            .iter()
+            .position(|key| *key == owner)
+            .ok_or(FeeError::NotExempt)?;
+        fee_config.exempt.swap_remove(position);
+    }
+
+    emit!(FeeExemptionSet {
+        mint: fee_config.mint,
+        owner,
+        exempt,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeFeeConfig<'info> {
+    #[account(
+        seeds = [b"token-config", mint.key().as_ref()],
+        bump = config.bump,
+        has_one = mint,
+        has_one = authority
+    )]
+    pub config: Account<'info, TokenConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = FeeConfig::LEN,
+        seeds = [b"fee-config", mint.key().as_ref()],
+        bump
+    )]
+    pub fee_config: Account<'info, FeeConfig>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(token::mint = mint)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeConfig<'info> {
+    #[account(
+        seeds = [b"token-config", fee_config.mint.as_ref()],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Account<'info, TokenConfig>,
+    #[account(
+        mut,
+        seeds = [b"fee-config", fee_config.mint.as_ref()],
+        bump = fee_config.bump
+    )]
+    pub fee_config: Account<'info, FeeConfig>,
+    #[account(token::mint = fee_config.mint)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeExemption<'info> {
+    #[account(
+        seeds = [b"token-config", fee_config.mint.as_ref()],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Account<'info, TokenConfig>,
+    #[account(
+        mut,
+        seeds = [b"fee-config", fee_config.mint.as_ref()],
+        bump = fee_config.bump
+    )]
+    pub fee_config: Account<'info, FeeConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[error_code]
+pub enum FeeError {
+    #[msg("Transfer fee rate exceeds the maximum")]
+    FeeRateTooHigh,
+    #[msg("Fee exemption list is full")]
+    TooManyExemptions,
+    #[msg("Owner is already fee exempt")]
+    AlreadyExempt,
+    #[msg("Owner is not fee exempt")]
+    NotExempt,
+    #[msg("Treasury account is required once the mint has a fee config")]
+    MissingTreasury,
+    #[msg("Treasury does not match the fee config")]
+    InvalidTreasury,
+}
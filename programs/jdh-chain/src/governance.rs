@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::TokenAccount;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+
+use crate::config::{PauseError, ProgramConfig};
 
 // Governance Proposal
 #[account]
@@ -29,7 +31,7 @@ pub struct Vote {
     pub bump: u8,                   // Bump seed
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
 pub enum VoteType {
     For,
     Against,
@@ -46,8 +48,48 @@ impl Vote {
     pub const LEN: usize = 8 + 32 + 32 + 1 + 8 + 8 + 1;
 }
 
+#[event]
+pub struct ProposalCreated {
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub title: String,
+    pub voting_ends_at: i64,
+    pub min_voting_power: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoteCast {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub vote_type: VoteType,
+    pub voting_power: u64,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub total_votes: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub executor: Pubkey,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoteReceiptClosed {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub timestamp: i64,
+}
+
 // Create Proposal
-pub fn create_proposal(
+pub fn create(
     ctx: Context<CreateProposal>,
     id: u64,
     title: String,
@@ -60,11 +102,11 @@ pub fn create_proposal(
 
     require!(
         title.len() <= Proposal::MAX_TITLE_LEN,
-        JdhChainError::TitleTooLong
+        GovernanceError::TitleTooLong
     );
     require!(
         description.len() <= Proposal::MAX_DESCRIPTION_LEN,
-        JdhChainError::DescriptionTooLong
+        GovernanceError::DescriptionTooLong
     );
 
     proposal.id = id;
@@ -80,12 +122,20 @@ pub fn create_proposal(
     proposal.min_voting_power = min_voting_power;
     proposal.bump = ctx.bumps.proposal;
 
-    msg!("Created proposal #{}: {}", id, proposal.title);
+    emit!(ProposalCreated {
+        proposal: proposal.key(),
+        id,
+        proposer: proposal.proposer,
+        title: proposal.title.clone(),
+        voting_ends_at: proposal.voting_ends_at,
+        min_voting_power,
+        timestamp: clock.unix_timestamp,
+    });
     Ok(())
 }
 
 // Vote on Proposal
-pub fn vote(ctx: Context<Vote>, vote_type: VoteType, voting_power: u64) -> Result<()> {
+pub fn vote(ctx: Context<CastVote>, vote_type: VoteType, voting_power: u64) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
     let vote_account = &mut ctx.accounts.vote;
     let clock = Clock::get()?;
@@ -93,20 +143,20 @@ pub fn vote(ctx: Context<Vote>, vote_type: VoteType, voting_power: u64) -> Resul
     // Check if voting is still open
     require!(
         clock.unix_timestamp < proposal.voting_ends_at,
-        JdhChainError::VotingClosed
+        GovernanceError::VotingClosed
     );
 
     // Check if user has already voted
     require!(
         vote_account.voting_power == 0,
-        JdhChainError::AlreadyVoted
+        GovernanceError::AlreadyVoted
     );
 
     // Check user's JDH balance (voting power)
     let user_balance = ctx.accounts.user_token_account.amount;
     require!(
         user_balance >= voting_power,
-        JdhChainError::InsufficientVotingPower
+        GovernanceError::InsufficientVotingPower
     );
 
     // Record vote
@@ -124,42 +174,75 @@ pub fn vote(ctx: Context<Vote>, vote_type: VoteType, voting_power: u64) -> Resul
     }
     proposal.total_votes += voting_power;
 
-    msg!("Vote recorded: {:?} with {} voting power", vote_type, voting_power);
+    emit!(VoteCast {
+        proposal: proposal.key(),
+        voter: vote_account.user,
+        vote_type,
+        voting_power,
+        votes_for: proposal.votes_for,
+        votes_against: proposal.votes_against,
+        total_votes: proposal.total_votes,
+        timestamp: clock.unix_timestamp,
+    });
     Ok(())
 }
 
 // Execute Proposal (if passed)
-pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+pub fn execute(ctx: Context<ExecuteProposal>) -> Result<()> {
     let proposal = &mut ctx.accounts.proposal;
     let clock = Clock::get()?;
 
     // Check if voting has ended
     require!(
         clock.unix_timestamp >= proposal.voting_ends_at,
-        JdhChainError::VotingStillOpen
+        GovernanceError::VotingStillOpen
     );
 
     // Check if already executed
     require!(
         !proposal.executed,
-        JdhChainError::ProposalAlreadyExecuted
+        GovernanceError::ProposalAlreadyExecuted
     );
 
     // Check if proposal passed (more votes for than against)
     require!(
         proposal.votes_for > proposal.votes_against,
-        JdhChainError::ProposalNotPassed
+        GovernanceError::ProposalNotPassed
     );
 
     // Check if minimum voting power was met
     require!(
         proposal.total_votes >= proposal.min_voting_power,
-        JdhChainError::InsufficientVotingPower
+        GovernanceError::InsufficientVotingPower
     );
 
     proposal.executed = true;
 
-    msg!("Proposal #{} executed successfully", proposal.id);
+    emit!(ProposalExecuted {
+        proposal: proposal.key(),
+        id: proposal.id,
+        executor: ctx.accounts.executor.key(),
+        votes_for: proposal.votes_for,
+        votes_against: proposal.votes_against,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Close a vote receipt once voting ended, rent goes back to the voter
+pub fn close_vote(ctx: Context<CloseVoteReceipt>) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        clock.unix_timestamp >= ctx.accounts.proposal.voting_ends_at,
+        GovernanceError::VotingStillOpen
+    );
+
+    emit!(VoteReceiptClosed {
+        proposal: ctx.accounts.proposal.key(),
+        voter: ctx.accounts.voter.key(),
+        timestamp: clock.unix_timestamp,
+    });
     Ok(())
 }
 
@@ -177,10 +260,16 @@ pub struct CreateProposal<'info> {
     #[account(mut)]
     pub proposer: Signer<'info>,
     pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.governance_paused @ PauseError::GovernancePaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 #[derive(Accounts)]
-pub struct Vote<'info> {
+pub struct CastVote<'info> {
     #[account(mut)]
     pub proposal: Account<'info, Proposal>,
     #[account(
@@ -192,11 +281,17 @@ pub struct Vote<'info> {
     )]
     pub vote: Account<'info, Vote>,
     #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub voter: Signer<'info>,
-    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.governance_paused @ PauseError::GovernancePaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 #[derive(Accounts)]
@@ -204,10 +299,32 @@ pub struct ExecuteProposal<'info> {
     #[account(mut)]
     pub proposal: Account<'info, Proposal>,
     pub executor: Signer<'info>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.governance_paused @ PauseError::GovernancePaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVoteReceipt<'info> {
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        close = voter,
+        has_one = proposal,
+        constraint = vote.user == voter.key() @ GovernanceError::NotVoteOwner,
+        seeds = [b"vote", voter.key().as_ref(), proposal.key().as_ref()],
+        bump = vote.bump
+    )]
+    pub vote: Account<'info, Vote>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
 }
 
 #[error_code]
-pub enum JdhChainError {
+pub enum GovernanceError {
     #[msg("Title too long")]
     TitleTooLong,
     #[msg("Description too long")]
@@ -224,4 +341,6 @@ pub enum JdhChainError {
     ProposalAlreadyExecuted,
     #[msg("Proposal did not pass")]
     ProposalNotPassed,
+    #[msg("Vote receipt belongs to another voter")]
+    NotVoteOwner,
 }
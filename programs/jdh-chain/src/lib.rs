@@ -1,13 +1,52 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
-
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::ExtensionType,
+    state::Mint as MintState,
+};
+use anchor_spl::token_interface::{
+    self, InitializeMint2, MetadataPointerInitialize, Mint, TokenAccount, TokenInterface,
+    TransferChecked, TransferFeeInitialize,
+};
+
+pub mod airdrop;
+pub mod allowance;
+pub mod authority;
+pub mod burn;
+pub mod compliance;
+pub mod config;
+pub mod fee;
+pub mod metadata;
+pub mod minter;
+pub mod multisig;
+pub mod stream;
+pub mod transfer;
 pub mod staking;
+pub mod escrow;
 pub mod governance;
 pub mod mining;
-
+pub mod vesting;
+
+// Handlers are called module-qualified. Airdrop, allowance, authority, escrow, fee, multisig
+// and stream keep theirs crate-private and named after the instruction, because names like
+// create/claim/revoke would otherwise clash across these glob re-exports.
+pub use airdrop::*;
+pub use allowance::*;
+pub use authority::*;
+pub use burn::*;
+pub use compliance::*;
+pub use config::*;
+pub use fee::*;
+pub use metadata::*;
+pub use minter::*;
+pub use multisig::*;
+pub use stream::*;
+pub use transfer::*;
 pub use staking::*;
+pub use escrow::*;
 pub use governance::*;
 pub use mining::*;
+pub use vesting::*;
 
 declare_id!("JDHChaiN11111111111111111111111111111111111");
 
@@ -15,29 +54,276 @@ declare_id!("JDHChaiN11111111111111111111111111111111111");
 pub mod jdh_chain {
     use super::*;
 
+    // ========== Program Config ==========
+
+    // Initialize Program Config (pause switches and guardian)
+    pub fn initialize_program_config(
+        ctx: Context<InitializeProgramConfig>,
+        guardian: Pubkey,
+    ) -> Result<()> {
+        config::initialize_program(ctx, guardian)
+    }
+
+    // Pause a Subsystem (guardian or admin)
+    pub fn pause(ctx: Context<SetPause>, subsystem: Subsystem) -> Result<()> {
+        config::pause_subsystem(ctx, subsystem)
+    }
+
+    // Unpause a Subsystem (admin only)
+    pub fn unpause(ctx: Context<SetPause>, subsystem: Subsystem) -> Result<()> {
+        config::unpause_subsystem(ctx, subsystem)
+    }
+
+    // Set Guardian (admin only)
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        config::update_guardian(ctx, guardian)
+    }
+
+    // ========== Multisig Operations ==========
+
+    // Create an M-of-N Multisig
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        id: u64,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        multisig::create(ctx, id, members, threshold)
+    }
+
+    // Propose an instruction for the multisig signer PDA to sign
+    pub fn propose_multisig_transaction(
+        ctx: Context<ProposeMultisigTransaction>,
+        program_id: Pubkey,
+        accounts: Vec<TransactionAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        multisig::propose(ctx, program_id, accounts, data)
+    }
+
+    // Approve a proposed multisig transaction
+    pub fn approve_multisig_transaction(ctx: Context<ApproveMultisigTransaction>) -> Result<()> {
+        multisig::approve(ctx)
+    }
+
+    // Execute a multisig transaction that reached its threshold
+    pub fn execute_multisig_transaction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteMultisigTransaction<'info>>,
+    ) -> Result<()> {
+        multisig::execute(ctx)
+    }
+
+    // Replace multisig members and threshold (through an executed transaction)
+    pub fn set_multisig_members(
+        ctx: Context<SetMultisigMembers>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        multisig::set_members(ctx, members, threshold)
+    }
+
+    // ========== Authority Operations ==========
+
+    // Propose a new staking pool authority (None cancels the proposal)
+    pub fn propose_staking_pool_authority(
+        ctx: Context<SetStakingPoolAuthority>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        authority::propose(&mut ctx.accounts.pool, new_authority)
+    }
+
+    // Accept the staking pool authority (proposed key signs)
+    pub fn accept_staking_pool_authority(ctx: Context<AcceptStakingPoolAuthority>) -> Result<()> {
+        let new_authority = ctx.accounts.new_authority.key();
+        authority::accept(&mut ctx.accounts.pool, new_authority)
+    }
+
+    // Renounce the staking pool authority for good
+    pub fn renounce_staking_pool_authority(ctx: Context<SetStakingPoolAuthority>) -> Result<()> {
+        authority::renounce(&mut ctx.accounts.pool)
+    }
+
+    // Propose a new mining vault authority (None cancels the proposal)
+    pub fn propose_mining_vault_authority(
+        ctx: Context<SetMiningVaultAuthority>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        authority::propose(&mut ctx.accounts.vault, new_authority)
+    }
+
+    // Accept the mining vault authority (proposed key signs)
+    pub fn accept_mining_vault_authority(ctx: Context<AcceptMiningVaultAuthority>) -> Result<()> {
+        let new_authority = ctx.accounts.new_authority.key();
+        authority::accept(&mut ctx.accounts.vault, new_authority)
+    }
+
+    // Renounce the mining vault authority for good
+    pub fn renounce_mining_vault_authority(ctx: Context<SetMiningVaultAuthority>) -> Result<()> {
+        authority::renounce(&mut ctx.accounts.vault)
+    }
+
+    // Propose a new token config authority (None cancels the proposal)
+    pub fn propose_token_authority(
+        ctx: Context<SetTokenAuthority>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        authority::propose(&mut ctx.accounts.config, new_authority)
+    }
+
+    // Accept the token config authority (proposed key signs)
+    pub fn accept_token_authority(ctx: Context<AcceptTokenAuthority>) -> Result<()> {
+        let new_authority = ctx.accounts.new_authority.key();
+        authority::accept(&mut ctx.accounts.config, new_authority)
+    }
+
+    // Renounce the token config authority for good
+    pub fn renounce_token_authority(ctx: Context<SetTokenAuthority>) -> Result<()> {
+        authority::renounce(&mut ctx.accounts.config)
+    }
+
     // ========== Token Operations ==========
 
-    // Initialize JDH Token Mint
-    pub fn initialize_mint(_ctx: Context<InitializeMint>, decimals: u8) -> Result<()> {
-        msg!("Initializing JDH Token Mint with {} decimals", decimals);
+    // Initialize JDH Token Mint (mint and freeze authorities are program PDAs)
+    pub fn initialize_mint(
+        ctx: Context<InitializeMint>,
+        decimals: u8,
+        max_supply: u64,
+        epoch_duration: i64,
+        initial_epoch_emission: u64,
+        emission_decay_bps: u16,
+        transfer_fee: Option<TransferFeeParams>,
+    ) -> Result<()> {
+        require!(
+            epoch_duration > 0 && emission_decay_bps <= 10_000,
+            TokenError::InvalidEmissionSchedule
+        );
+
+        // Token-2022 mints get a metadata pointer to themselves so JDH metadata lives on the mint,
+        // and optionally a transfer fee withheld by Token-2022
+        let is_token_2022 = ctx.accounts.token_program.key() == anchor_spl::token_2022::ID;
+        if let Some(fee) = transfer_fee {
+            require!(
+                is_token_2022 && fee.transfer_fee_bps <= 10_000,
+                TokenError::InvalidTransferFee
+            );
+        }
+        let mut extensions = Vec::new();
+        if is_token_2022 {
+            extensions.push(ExtensionType::MetadataPointer);
+        }
+        if transfer_fee.is_some() {
+            extensions.push(ExtensionType::TransferFeeConfig);
+        }
+        let space = ExtensionType::try_calculate_account_len::<MintState>(&extensions)?;
+
+        system_program::create_account(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            ctx.accounts.token_program.key,
+        )?;
+
+        if is_token_2022 {
+            token_interface::metadata_pointer_initialize(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    MetadataPointerInitialize {
+                        token_program_id: ctx.accounts.token_program.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                    },
+                ),
+                Some(ctx.accounts.mint_authority.key()),
+                Some(ctx.accounts.mint.key()),
+            )?;
+        }
+
+        if let Some(fee) = transfer_fee {
+            // The mint-authority PDA may change the fee and withdraw withheld fees
+            token_interface::transfer_fee_initialize(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferFeeInitialize {
+                        token_program_id: ctx.accounts.token_program.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                    },
+                ),
+                Some(ctx.accounts.mint_authority.key),
+                Some(ctx.accounts.mint_authority.key),
+                fee.transfer_fee_bps,
+                fee.maximum_fee,
+            )?;
+        }
+
+        token_interface::initialize_mint2(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeMint2 {
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            decimals,
+            ctx.accounts.mint_authority.key,
+            Some(ctx.accounts.freeze_authority.key),
+        )?;
+
+        let config = &mut ctx.accounts.config;
+        config.mint = ctx.accounts.mint.key();
+        config.authority = ctx.accounts.authority.key();
+        config.pending_authority = None;
+        config.compliance_authority = ctx.accounts.authority.key();
+        config.decimals = decimals;
+        config.mint_authority_bump = ctx.bumps.mint_authority;
+        config.freeze_authority_bump = ctx.bumps.freeze_authority;
+        config.max_supply = max_supply;
+        config.total_minted = 0;
+        config.emission_start = Clock::get()?.unix_timestamp;
+        config.epoch_duration = epoch_duration;
+        config.initial_epoch_emission = initial_epoch_emission;
+        config.emission_decay_bps = emission_decay_bps;
+        config.current_epoch = 0;
+        config.epoch_minted = 0;
+        config.bump = ctx.bumps.config;
+
+        emit!(MintInitialized {
+            mint: config.mint,
+            authority: config.authority,
+            token_program: ctx.accounts.token_program.key(),
+            decimals,
+            max_supply,
+            epoch_duration,
+            initial_epoch_emission,
+            emission_decay_bps,
+            transfer_fee,
+            timestamp: config.emission_start,
+        });
         Ok(())
     }
 
     // Mint JDH Tokens
     pub fn mint_tokens(ctx: Context<MintTokens>, amount: u64) -> Result<()> {
-        msg!("Minting {} JDH tokens", amount);
-        
+
+        let clock = Clock::get()?;
+        ctx.accounts.config.record_emission(amount, clock.unix_timestamp)?;
+        ctx.accounts.minter_allowance.debit(amount, clock.unix_timestamp)?;
+
+        let mint_key = ctx.accounts.mint.key();
         let seeds = &[
             b"mint-authority",
-            ctx.accounts.mint.key().as_ref(),
-            &[ctx.bumps.mint_authority],
+            mint_key.as_ref(),
+            &[ctx.accounts.config.mint_authority_bump],
         ];
         let signer = &[&seeds[..]];
 
-        token::mint_to(
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                token::MintTo {
+                token_interface::MintTo {
                     mint: ctx.accounts.mint.to_account_info(),
                     to: ctx.accounts.to.to_account_info(),
                     authority: ctx.accounts.mint_authority.to_account_info(),
@@ -47,36 +333,164 @@ pub mod jdh_chain {
             amount,
         )?;
 
+        emit!(TokensMinted {
+            mint: mint_key,
+            minter: ctx.accounts.minter.key(),
+            to: ctx.accounts.to.key(),
+            amount,
+            minter_total_minted: ctx.accounts.minter_allowance.total_minted,
+            total_minted: ctx.accounts.config.total_minted,
+            timestamp: clock.unix_timestamp,
+        });
+
         Ok(())
     }
 
-    // Transfer JDH Tokens
+    // Add a Minter with its own allowances
+    pub fn add_minter(
+        ctx: Context<AddMinter>,
+        total_allowance: u64,
+        window_allowance: u64,
+        window_duration: i64,
+    ) -> Result<()> {
+        minter::add(ctx, total_allowance, window_allowance, window_duration)
+    }
+
+    // Remove a Minter
+    pub fn remove_minter(ctx: Context<RemoveMinter>) -> Result<()> {
+        minter::remove(ctx)
+    }
+
+    // Initialize on-chain JDH metadata (Token-2022 mints only)
+    pub fn initialize_metadata(
+        ctx: Context<InitializeMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        metadata::initialize(ctx, name, symbol, uri)
+    }
+
+    // Update a JDH metadata field (name, symbol, URI or additional key)
+    pub fn update_metadata_field(
+        ctx: Context<UpdateMetadataField>,
+        field: MetadataField,
+        value: String,
+    ) -> Result<()> {
+        metadata::update_field(ctx, field, value)
+    }
+
+    // Transfer JDH Tokens, routing the protocol fee to the treasury
     pub fn transfer_tokens(ctx: Context<TransferTokens>, amount: u64) -> Result<()> {
-        msg!("Transferring {} JDH tokens", amount);
-        
-        token::transfer(
+        let mut fee_config =
+            fee::load_config(&ctx.accounts.fee_config, ctx.accounts.treasury.as_ref())?;
+        let fee = fee::fee_owed(
+            fee_config.as_ref(),
+            amount,
+            &ctx.accounts.from.owner,
+            &ctx.accounts.to.owner,
+        );
+        let net_amount = amount - fee;
+
+        token_interface::transfer_checked(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: ctx.accounts.from.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
                     to: ctx.accounts.to.to_account_info(),
                     authority: ctx.accounts.authority.to_account_info(),
                 },
             ),
-            amount,
+            net_amount,
+            ctx.accounts.mint.decimals,
         )?;
 
+        if fee > 0 {
+            let treasury = ctx
+                .accounts
+                .treasury
+                .as_ref()
+                .ok_or(FeeError::MissingTreasury)?;
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.from.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: treasury.to_account_info(),
+                        authority: ctx.accounts.authority.to_account_info(),
+                    },
+                ),
+                fee,
+                ctx.accounts.mint.decimals,
+            )?;
+
+            if let Some(fee_config) = fee_config.as_mut() {
+                fee::record_collected(&ctx.accounts.fee_config, fee_config, fee)?;
+            }
+        }
+
+        emit!(TokensTransferred {
+            mint: ctx.accounts.mint.key(),
+            from: ctx.accounts.from.key(),
+            to: ctx.accounts.to.key(),
+            authority: ctx.accounts.authority.key(),
+            amount: net_amount,
+            fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
         Ok(())
     }
 
+    // Transfer JDH Tokens to many recipients atomically
+    pub fn batch_transfer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchTransfer<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        transfer::batch(ctx, amounts)
+    }
+
+    // Initialize the transfer fee config (rate, cap and treasury)
+    pub fn initialize_fee_config(
+        ctx: Context<InitializeFeeConfig>,
+        transfer_fee_bps: u16,
+        max_fee: u64,
+    ) -> Result<()> {
+        fee::initialize(ctx, transfer_fee_bps, max_fee)
+    }
+
+    // Update the transfer fee rate, cap and treasury
+    pub fn update_fee_config(
+        ctx: Context<UpdateFeeConfig>,
+        transfer_fee_bps: u16,
+        max_fee: u64,
+    ) -> Result<()> {
+        fee::update(ctx, transfer_fee_bps, max_fee)
+    }
+
+    // Add or remove a transfer fee exemption (exchanges, program vaults)
+    pub fn set_fee_exemption(
+        ctx: Context<SetFeeExemption>,
+        owner: Pubkey,
+        exempt: bool,
+    ) -> Result<()> {
+        fee::set_exemption(ctx, owner, exempt)
+    }
+
     // Burn JDH Tokens
-    pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
-        msg!("Burning {} JDH tokens", amount);
-        
-        token::burn(
+    pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64, reason: BurnReason) -> Result<()> {
+        // Fee and buyback burns feed protocol metrics, so only the token authority records them
+        require!(
+            reason == BurnReason::User
+                || ctx.accounts.authority.key() == ctx.accounts.config.authority,
+            BurnError::UnauthorizedBurnReason
+        );
+
+        token_interface::burn(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                token::Burn {
+                token_interface::Burn {
                     mint: ctx.accounts.mint.to_account_info(),
                     from: ctx.accounts.from.to_account_info(),
                     authority: ctx.accounts.authority.to_account_info(),
@@ -85,34 +499,109 @@ pub mod jdh_chain {
             amount,
         )?;
 
+        let clock = Clock::get()?;
+        let burn_ledger = &mut ctx.accounts.burn_ledger;
+        if burn_ledger.mint == Pubkey::default() {
+            burn_ledger.mint = ctx.accounts.mint.key();
+            burn_ledger.bump = ctx.bumps.burn_ledger;
+        }
+        burn_ledger.record(amount, reason)?;
+
+        let user_burn = &mut ctx.accounts.user_burn;
+        if user_burn.user == Pubkey::default() {
+            user_burn.user = ctx.accounts.authority.key();
+            user_burn.mint = ctx.accounts.mint.key();
+            user_burn.bump = ctx.bumps.user_burn;
+        }
+        user_burn.record(amount, reason, clock.unix_timestamp)?;
+
+        emit!(TokensBurned {
+            mint: ctx.accounts.mint.key(),
+            user: ctx.accounts.authority.key(),
+            from: ctx.accounts.from.key(),
+            amount,
+            reason,
+            user_total_burned: user_burn.total_burned,
+            total_burned: burn_ledger.total_burned,
+            timestamp: clock.unix_timestamp,
+        });
+
         Ok(())
     }
 
+    // ========== Compliance Operations ==========
+
+    // Freeze a Token Account (compliance authority)
+    pub fn freeze_account(ctx: Context<FreezeTokenAccount>, reason_code: u16) -> Result<()> {
+        compliance::freeze(ctx, reason_code)
+    }
+
+    // Thaw a Token Account (compliance authority)
+    pub fn thaw_account(ctx: Context<ThawTokenAccount>, reason_code: u16) -> Result<()> {
+        compliance::thaw(ctx, reason_code)
+    }
+
+    // Set Compliance Authority (token authority)
+    pub fn set_compliance_authority(
+        ctx: Context<SetComplianceAuthority>,
+        compliance_authority: Pubkey,
+    ) -> Result<()> {
+        compliance::update_compliance_authority(ctx, compliance_authority)
+    }
+
     // ========== Staking Operations ==========
 
-    // Initialize Staking Pool
+    // Initialize Staking Pool (token authority, who becomes the pool authority)
     pub fn initialize_staking_pool(
         ctx: Context<InitializeStakingPool>,
-        apy: u16,
+        reward_rate: u64,
         min_stake: u64,
-        lock_period: i64,
+        lock_tiers: Vec<LockTier>,
     ) -> Result<()> {
+        staking::validate_lock_tiers(&lock_tiers)?;
+
+        let clock = Clock::get()?;
         let pool = &mut ctx.accounts.pool;
         pool.mint = ctx.accounts.mint.key();
         pool.total_staked = 0;
-        pool.apy = apy;
+        pool.total_weight = 0;
+        pool.reward_rate = reward_rate;
+        pool.acc_reward_per_share = 0;
+        pool.last_update = clock.unix_timestamp;
+        pool.reward_end = clock.unix_timestamp;
+        pool.remaining_rewards = 0;
         pool.min_stake = min_stake;
-        pool.lock_period = lock_period;
-        pool.pool_authority = ctx.accounts.pool_authority.key();
+        pool.stake_vault = ctx.accounts.stake_vault.key();
+        pool.rewards_vault = ctx.accounts.rewards_vault.key();
+        pool.vault_authority_bump = ctx.bumps.vault_authority;
+        pool.pool_authority = ctx.accounts.authority.key();
+        pool.pending_authority = None;
         pool.bump = ctx.bumps.pool;
-
-        msg!("Initialized staking pool with {}% APY", apy as f64 / 100.0);
+        pool.lock_tiers = lock_tiers;
+        pool.compound_tip_bps = 0;
+
+        emit!(StakingPoolInitialized {
+            pool: pool.key(),
+            mint: pool.mint,
+            pool_authority: pool.pool_authority,
+            stake_vault: pool.stake_vault,
+            rewards_vault: pool.rewards_vault,
+            reward_rate,
+            min_stake,
+            lock_tiers: pool.lock_tiers.clone(),
+            timestamp: clock.unix_timestamp,
+        });
         Ok(())
     }
 
-    // Stake JDH Tokens
-    pub fn stake_tokens(ctx: Context<Stake>, amount: u64) -> Result<()> {
-        staking::stake(ctx, amount)
+    // Stake JDH Tokens into a lock tier, in a new or existing position
+    pub fn stake_tokens(
+        ctx: Context<Stake>,
+        position_id: u64,
+        amount: u64,
+        tier: u8,
+    ) -> Result<()> {
+        staking::stake(ctx, position_id, amount, tier)
     }
 
     // Unstake JDH Tokens
@@ -125,6 +614,36 @@ pub mod jdh_chain {
         staking::claim_rewards(ctx)
     }
 
+    // Set the Staking Reward Rate (tokens per second shared by all stakers)
+    pub fn set_staking_reward_rate(ctx: Context<SetRewardRate>, reward_rate: u64) -> Result<()> {
+        staking::update_reward_rate(ctx, reward_rate)
+    }
+
+    // Fund Staking Rewards (emission runs until the funded budget is used up)
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        staking::fund(ctx, amount)
+    }
+
+    // Compound Staking Rewards into principal (owner, or any cranker if auto-compound is on)
+    pub fn compound_rewards(ctx: Context<CompoundRewards>) -> Result<()> {
+        staking::compound(ctx)
+    }
+
+    // Opt a stake position in or out of auto-compounding
+    pub fn set_auto_compound(ctx: Context<SetAutoCompound>, enabled: bool) -> Result<()> {
+        staking::toggle_auto_compound(ctx, enabled)
+    }
+
+    // Set the tip paid to crankers for compounding (basis points of the rewards)
+    pub fn set_compound_tip(ctx: Context<SetRewardRate>, compound_tip_bps: u16) -> Result<()> {
+        staking::update_compound_tip(ctx, compound_tip_bps)
+    }
+
+    // Close an empty Stake Account (rent back to the user)
+    pub fn close_user_stake(ctx: Context<CloseUserStake>) -> Result<()> {
+        staking::close_stake(ctx)
+    }
+
     // ========== Governance Operations ==========
 
     // Create Governance Proposal
@@ -136,17 +655,22 @@ pub mod jdh_chain {
         voting_duration: i64,
         min_voting_power: u64,
     ) -> Result<()> {
-        governance::create_proposal(ctx, id, title, description, voting_duration, min_voting_power)
+        governance::create(ctx, id, title, description, voting_duration, min_voting_power)
     }
 
     // Vote on Proposal
-    pub fn vote_proposal(ctx: Context<Vote>, vote_type: VoteType, voting_power: u64) -> Result<()> {
+    pub fn vote_proposal(ctx: Context<CastVote>, vote_type: VoteType, voting_power: u64) -> Result<()> {
         governance::vote(ctx, vote_type, voting_power)
     }
 
     // Execute Proposal
     pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
-        governance::execute_proposal(ctx)
+        governance::execute(ctx)
+    }
+
+    // Close a Vote Receipt after voting ended (rent back to the voter)
+    pub fn close_vote_receipt(ctx: Context<CloseVoteReceipt>) -> Result<()> {
+        governance::close_vote(ctx)
     }
 
     // ========== Mining Operations ==========
@@ -173,68 +697,326 @@ pub mod jdh_chain {
     pub fn pay_mining_entry_fee(ctx: Context<PayEntryFee>, fee: u64) -> Result<()> {
         mining::pay_entry_fee(ctx, fee)
     }
+
+    // Close an empty Mining Deposit (rent back to the user)
+    pub fn close_mining_deposit(ctx: Context<CloseMiningDeposit>) -> Result<()> {
+        mining::close_deposit(ctx)
+    }
+
+    // ========== Vesting Operations ==========
+
+    // Create Vesting Schedule
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        id: u64,
+        total_amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        duration: i64,
+        period: Option<i64>,
+        revocable: bool,
+    ) -> Result<()> {
+        vesting::create_schedule(ctx, id, total_amount, start_ts, cliff_ts, duration, period, revocable)
+    }
+
+    // Claim Vested JDH
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        vesting::claim(ctx)
+    }
+
+    // Revoke Vesting Schedule
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        vesting::revoke(ctx)
+    }
+
+    // ========== Airdrop Operations ==========
+
+    // Create Merkle Airdrop Distributor
+    pub fn create_distributor(
+        ctx: Context<CreateDistributor>,
+        id: u64,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        num_claimants: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        airdrop::create(ctx, id, merkle_root, total_amount, num_claimants, expires_at)
+    }
+
+    // Claim Airdrop Allocation
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        index: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        airdrop::claim(ctx, index, amount, proof)
+    }
+
+    // Claw Back Unclaimed Airdrop Funds
+    pub fn clawback(ctx: Context<Clawback>) -> Result<()> {
+        airdrop::clawback(ctx)
+    }
+
+    // ========== Escrow Operations ==========
+
+    // Create Escrow
+    pub fn create_escrow(
+        ctx: Context<CreateEscrow>,
+        id: u64,
+        amount: u64,
+        arbiter: Option<Pubkey>,
+        release_time: Option<i64>,
+    ) -> Result<()> {
+        escrow::create(ctx, id, amount, arbiter, release_time)
+    }
+
+    // Release Escrow to Recipient
+    pub fn release_escrow(ctx: Context<ReleaseEscrow>) -> Result<()> {
+        escrow::release(ctx)
+    }
+
+    // Refund Escrow to Sender
+    pub fn refund_escrow(ctx: Context<RefundEscrow>) -> Result<()> {
+        escrow::refund(ctx)
+    }
+
+    // Dispute Escrow
+    pub fn dispute_escrow(ctx: Context<DisputeEscrow>) -> Result<()> {
+        escrow::dispute(ctx)
+    }
+
+    // ========== Streaming Operations ==========
+
+    // Create Payment Stream
+    pub fn create_stream(
+        ctx: Context<CreateStream>,
+        id: u64,
+        rate_per_second: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        stream::create(ctx, id, rate_per_second, start_time, end_time)
+    }
+
+    // Withdraw Accrued Stream Tokens
+    pub fn withdraw_from_stream(ctx: Context<WithdrawFromStream>) -> Result<()> {
+        stream::withdraw(ctx)
+    }
+
+    // Cancel Payment Stream
+    pub fn cancel_stream(ctx: Context<CancelStream>) -> Result<()> {
+        stream::cancel(ctx)
+    }
+
+    // ========== Allowance Operations ==========
+
+    // Grant a Per-Period Spending Allowance
+    pub fn grant_allowance(
+        ctx: Context<GrantAllowance>,
+        limit_per_period: u64,
+        period_duration: i64,
+        expires_at: i64,
+    ) -> Result<()> {
+        allowance::grant(ctx, limit_per_period, period_duration, expires_at)
+    }
+
+    // Spend from an Allowance
+    pub fn spend_allowance(ctx: Context<SpendAllowance>, amount: u64) -> Result<()> {
+        allowance::spend(ctx, amount)
+    }
+
+    // Revoke an Allowance
+    pub fn revoke_allowance(ctx: Context<RevokeAllowance>) -> Result<()> {
+        allowance::revoke(ctx)
+    }
 }
 
 // ========== Token Account Structs ==========
 
 #[derive(Accounts)]
 pub struct InitializeMint<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = TokenConfig::LEN,
+        seeds = [b"token-config", mint.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, TokenConfig>,
+    // Created in the handler so Token-2022 extensions can be added first
+    #[account(mut)]
+    pub mint: Signer<'info>,
+    /// CHECK: PDA signer for minting, holds no data
+    #[account(seeds = [b"mint-authority", mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    /// CHECK: PDA signer for freezing, holds no data
+    #[account(seeds = [b"freeze-authority", mint.key().as_ref()], bump)]
+    pub freeze_authority: UncheckedAccount<'info>,
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    pub mint_authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct MintTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"token-config", mint.key().as_ref()],
+        bump = config.bump,
+        has_one = mint
+    )]
+    pub config: Account<'info, TokenConfig>,
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
-    pub mint_authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA signer for minting, holds no data
+    #[account(
+        seeds = [b"mint-authority", mint.key().as_ref()],
+        bump = config.mint_authority_bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        has_one = config,
+        has_one = minter,
+        seeds = [b"minter", config.key().as_ref(), minter.key().as_ref()],
+        bump = minter_allowance.bump
+    )]
+    pub minter_allowance: Account<'info, MinterAllowance>,
+    pub minter: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 #[derive(Accounts)]
 pub struct TransferTokens<'info> {
+    /// CHECK: The mint's fee-config PDA, read by fee::load_config once it has been initialized
+    #[account(mut, seeds = [b"fee-config", mint.key().as_ref()], bump)]
+    pub fee_config: UncheckedAccount<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
+    pub from: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    // Required once the mint has a fee config
+    #[account(mut)]
+    pub treasury: Option<InterfaceAccount<'info, TokenAccount>>,
     pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 #[derive(Accounts)]
 pub struct BurnTokens<'info> {
+    #[account(
+        seeds = [b"token-config", mint.key().as_ref()],
+        bump = config.bump,
+        has_one = mint
+    )]
+    pub config: Account<'info, TokenConfig>,
     #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = BurnLedger::LEN,
+        seeds = [b"burn-ledger", mint.key().as_ref()],
+        bump
+    )]
+    pub burn_ledger: Account<'info, BurnLedger>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = UserBurnRecord::LEN,
+        seeds = [b"user-burn", mint.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub user_burn: Account<'info, UserBurnRecord>,
     #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
     pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 // ========== Staking Account Structs ==========
 
 #[derive(Accounts)]
 pub struct InitializeStakingPool<'info> {
+    // Only the token authority may create the mint's single staking pool
+    #[account(
+        seeds = [b"token-config", mint.key().as_ref()],
+        bump = config.bump,
+        has_one = mint,
+        has_one = authority
+    )]
+    pub config: Account<'info, TokenConfig>,
     #[account(
         init,
-        payer = pool_authority,
+        payer = authority,
         space = StakingPool::LEN,
         seeds = [b"staking-pool", mint.key().as_ref()],
         bump
     )]
     pub pool: Account<'info, StakingPool>,
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: PDA signer owning the pool vaults, holds no data
+    #[account(seeds = [b"pool-authority", pool.key().as_ref()], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"stake-vault", pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+        token::token_program = token_program
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"rewards-vault", pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+        token::token_program = token_program
+    )]
+    pub rewards_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub pool_authority: Signer<'info>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.staking_paused @ PauseError::StakingPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 // Re-export staking, governance, and mining structs
-pub use staking::{Stake, Unstake, ClaimRewards, StakingPool, UserStake, JdhChainError as StakingError};
-pub use governance::{CreateProposal, Vote, ExecuteProposal, Proposal, Vote as VoteAccount, VoteType, JdhChainError as GovernanceError};
-pub use mining::{InitializeVault, Deposit, Withdraw, PayEntryFee, MiningVault, UserMiningDeposit, MiningTier, MiningError};
+pub use staking::{Stake, Unstake, ClaimRewards, SetRewardRate, FundRewards, CompoundRewards, SetAutoCompound, CloseUserStake, StakingPool, UserStake, UserStakeCounter, StakingError};
+pub use governance::{CreateProposal, CastVote, ExecuteProposal, CloseVoteReceipt, Proposal, Vote, Vote as VoteAccount, VoteType, GovernanceError};
+pub use mining::{InitializeVault, Deposit, Withdraw, PayEntryFee, CloseMiningDeposit, MiningVault, UserMiningDeposit, MiningTier, MiningError};
@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token_interface::{
+    self, spl_token_metadata_interface::state::Field, Mint, Token2022, TokenMetadataInitialize,
+    TokenMetadataUpdateField,
+};
+
+use crate::config::TokenConfig;
+
+// Metadata field selector (mirrors the token-metadata interface Field)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum MetadataField {
+    Name,
+    Symbol,
+    Uri,
+    Key(String), // Additional field, e.g. "website"
+}
+
+impl From<MetadataField> for Field {
+    fn from(field: MetadataField) -> Self {
+        match field {
+            MetadataField::Name => Field::Name,
+            MetadataField::Symbol => Field::Symbol,
+            MetadataField::Uri => Field::Uri,
+            MetadataField::Key(key) => Field::Key(key),
+        }
+    }
+}
+
+#[event]
+pub struct MetadataInitialized {
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MetadataFieldUpdated {
+    pub mint: Pubkey,
+    pub field: MetadataField,
+    pub value: String,
+    pub timestamp: i64,
+}
+
+// Initialize JDH metadata on the mint (Token-2022 only)
+pub fn initialize(
+    ctx: Context<InitializeMetadata>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    let mint_key = ctx.accounts.mint.key();
+
+    token_interface::token_metadata_initialize(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TokenMetadataInitialize {
+                program_id: ctx.accounts.token_program.to_account_info(),
+                metadata: ctx.accounts.mint.to_account_info(),
+                update_authority: ctx.accounts.mint_authority.to_account_info(),
+                mint_authority: ctx.accounts.mint_authority.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+            },
+            &[&[
+                b"mint-authority",
+                mint_key.as_ref(),
+                &[ctx.accounts.config.mint_authority_bump],
+            ]],
+        ),
+        name.clone(),
+        symbol.clone(),
+        uri.clone(),
+    )?;
+
+    top_up_rent(
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    emit!(MetadataInitialized {
+        mint: mint_key,
+        name,
+        symbol,
+        uri,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Update a metadata field, adding it if it does not exist yet
+pub fn update_field(
+    ctx: Context<UpdateMetadataField>,
+    field: MetadataField,
+    value: String,
+) -> Result<()> {
+    let mint_key = ctx.accounts.mint.key();
+
+    token_interface::token_metadata_update_field(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TokenMetadataUpdateField {
+                program_id: ctx.accounts.token_program.to_account_info(),
+                metadata: ctx.accounts.mint.to_account_info(),
+                update_authority: ctx.accounts.mint_authority.to_account_info(),
+            },
+            &[&[
+                b"mint-authority",
+                mint_key.as_ref(),
+                &[ctx.accounts.config.mint_authority_bump],
+            ]],
+        ),
+        field.clone().into(),
+        value.clone(),
+    )?;
+
+    top_up_rent(
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    emit!(MetadataFieldUpdated {
+        mint: mint_key,
+        field,
+        value,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// The metadata extension reallocs the mint, so keep it rent exempt
+fn top_up_rent<'info>(
+    account: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    let required = Rent::get()?.minimum_balance(account.data_len());
+    let missing = required.saturating_sub(account.lamports());
+
+    if missing > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                Transfer {
+                    from: payer,
+                    to: account,
+                },
+            ),
+            missing,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeMetadata<'info> {
+    #[account(
+        seeds = [b"token-config", mint.key().as_ref()],
+        bump = config.bump,
+        has_one = mint,
+        has_one = authority
+    )]
+    pub config: Account<'info, TokenConfig>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: PDA signer for minting and metadata updates, holds no data
+    #[account(
+        seeds = [b"mint-authority", mint.key().as_ref()],
+        bump = config.mint_authority_bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadataField<'info> {
+    #[account(
+        seeds = [b"token-config", mint.key().as_ref()],
+        bump = config.bump,
+        has_one = mint,
+        has_one = authority
+    )]
+    pub config: Account<'info, TokenConfig>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: PDA signer for minting and metadata updates, holds no data
+    #[account(
+        seeds = [b"mint-authority", mint.key().as_ref()],
+        bump = config.mint_authority_bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
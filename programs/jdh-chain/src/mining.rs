@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::config::{PauseError, ProgramConfig};
+use crate::transfer::transfer_in;
 
 // Mining Vault Account
 #[account]
@@ -7,6 +10,7 @@ pub struct MiningVault {
     pub mint: Pubkey,                    // JDH Token mint
     pub total_deposited: u64,            // Total deposited amount
     pub pool_authority: Pubkey,           // Pool authority (receives entry fees)
+    pub pending_authority: Option<Pubkey>, // Proposed authority, must accept
     pub entry_fee_cap: u64,              // Maximum entry fee (in lamports)
     pub bump: u8,                        // Bump seed
 }
@@ -27,7 +31,7 @@ pub struct UserMiningDeposit {
 }
 
 // Mining Tier Enum
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum MiningTier {
     Bronze,    // 0-999 JDH deposit
     Silver,    // 1000-9999 JDH deposit
@@ -56,13 +60,60 @@ impl MiningTier {
 }
 
 impl MiningVault {
-    pub const LEN: usize = 8 + 32 + 8 + 32 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + 8 + 32 + (1 + 32) + 8 + 1;
 }
 
 impl UserMiningDeposit {
     pub const LEN: usize = 8 + 32 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
 }
 
+#[event]
+pub struct MiningVaultInitialized {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub pool_authority: Pubkey,
+    pub entry_fee_cap: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MiningDeposited {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub deposit_amount: u64,
+    pub tier: MiningTier,
+    pub total_deposited: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MiningWithdrawn {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub deposit_amount: u64,
+    pub tier: MiningTier,
+    pub total_deposited: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EntryFeePaid {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub fee: u64,
+    pub total_entry_fees_paid: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MiningDepositClosed {
+    pub user: Pubkey,
+    pub total_entry_fees_paid: u64,
+    pub timestamp: i64,
+}
+
 // Initialize Mining Vault
 pub fn initialize_vault(
     ctx: Context<InitializeVault>,
@@ -72,10 +123,17 @@ pub fn initialize_vault(
     vault.mint = ctx.accounts.mint.key();
     vault.total_deposited = 0;
     vault.pool_authority = ctx.accounts.pool_authority.key();
+    vault.pending_authority = None;
     vault.entry_fee_cap = entry_fee_cap;
     vault.bump = ctx.bumps.vault;
 
-    msg!("Initialized Mining Vault with entry fee cap: {}", entry_fee_cap);
+    emit!(MiningVaultInitialized {
+        vault: vault.key(),
+        mint: vault.mint,
+        pool_authority: vault.pool_authority,
+        entry_fee_cap,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
 
@@ -86,15 +144,13 @@ pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
     let clock = Clock::get()?;
 
     // Transfer tokens to vault
-    token::transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user_token_account.to_account_info(),
-                to: ctx.accounts.vault_token_account.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            },
-        ),
+    let received = transfer_in(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.user_token_account.to_account_info(),
+        &mut ctx.accounts.vault_token_account,
+        ctx.accounts.user.to_account_info(),
+        &[],
         amount,
     )?;
 
@@ -110,15 +166,23 @@ pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
         user_deposit.bump = ctx.bumps.user_deposit;
     }
 
-    user_deposit.deposit_amount += amount;
+    user_deposit.deposit_amount += received;
     
     // Update tier based on total deposit
     user_deposit.tier = MiningTier::from_deposit(user_deposit.deposit_amount);
     user_deposit.daily_points_cap = user_deposit.tier.daily_cap();
 
-    vault.total_deposited += amount;
-
-    msg!("Deposited {} JDH, new tier: {:?}", amount, user_deposit.tier);
+    vault.total_deposited += received;
+
+    emit!(MiningDeposited {
+        vault: vault.key(),
+        user: user_deposit.user,
+        amount: received,
+        deposit_amount: user_deposit.deposit_amount,
+        tier: user_deposit.tier,
+        total_deposited: vault.total_deposited,
+        timestamp: clock.unix_timestamp,
+    });
     Ok(())
 }
 
@@ -142,11 +206,12 @@ pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
     );
 
     // Transfer tokens back to user
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.user_token_account.to_account_info(),
                 authority: vault.to_account_info(),
             },
@@ -157,6 +222,7 @@ pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
             ]],
         ),
         amount,
+        ctx.accounts.mint.decimals,
     )?;
 
     user_deposit.deposit_amount -= amount;
@@ -168,7 +234,15 @@ pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
 
     vault.total_deposited -= amount;
 
-    msg!("Withdrew {} JDH", amount);
+    emit!(MiningWithdrawn {
+        vault: vault.key(),
+        user: user_deposit.user,
+        amount,
+        deposit_amount: user_deposit.deposit_amount,
+        tier: user_deposit.tier,
+        total_deposited: vault.total_deposited,
+        timestamp: clock.unix_timestamp,
+    });
     Ok(())
 }
 
@@ -184,21 +258,46 @@ pub fn pay_entry_fee(ctx: Context<PayEntryFee>, fee: u64) -> Result<()> {
     );
 
     // Transfer entry fee to pool authority
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.user_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.pool_token_account.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
         fee,
+        ctx.accounts.mint.decimals,
     )?;
 
     user_deposit.total_entry_fees_paid += fee;
 
-    msg!("Paid entry fee: {} JDH", fee);
+    emit!(EntryFeePaid {
+        vault: vault.key(),
+        user: user_deposit.user,
+        fee,
+        total_entry_fees_paid: user_deposit.total_entry_fees_paid,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Close an empty deposit account, rent goes back to the user
+pub fn close_deposit(ctx: Context<CloseMiningDeposit>) -> Result<()> {
+    let user_deposit = &ctx.accounts.user_deposit;
+
+    require!(
+        user_deposit.deposit_amount == 0,
+        MiningError::DepositNotEmpty
+    );
+
+    emit!(MiningDepositClosed {
+        user: user_deposit.user,
+        total_entry_fees_paid: user_deposit.total_entry_fees_paid,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
 
@@ -214,16 +313,24 @@ pub struct InitializeVault<'info> {
         bump
     )]
     pub vault: Account<'info, MiningVault>,
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
     pub pool_authority: Signer<'info>,
     pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.mining_paused @ PauseError::MiningPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(mut)]
     pub vault: Account<'info, MiningVault>,
+    #[account(address = vault.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
         init_if_needed,
         payer = user,
@@ -235,17 +342,25 @@ pub struct Deposit<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     #[account(mut, constraint = user_token_account.owner == user.key())]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut, constraint = vault_token_account.owner == vault.key())]
-    pub vault_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.mining_paused @ PauseError::MiningPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
     #[account(mut)]
     pub vault: Account<'info, MiningVault>,
+    #[account(address = vault.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         has_one = user,
@@ -256,16 +371,24 @@ pub struct Withdraw<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     #[account(mut, constraint = user_token_account.owner == user.key())]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut, constraint = vault_token_account.owner == vault.key())]
-    pub vault_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.mining_paused @ PauseError::MiningPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 #[derive(Accounts)]
 pub struct PayEntryFee<'info> {
     #[account(mut)]
     pub vault: Account<'info, MiningVault>,
+    #[account(address = vault.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         has_one = user,
@@ -276,10 +399,30 @@ pub struct PayEntryFee<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     #[account(mut, constraint = user_token_account.owner == user.key())]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut, constraint = pool_token_account.owner == vault.pool_authority)]
-    pub pool_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.mining_paused @ PauseError::MiningPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMiningDeposit<'info> {
+    #[account(
+        mut,
+        close = user,
+        has_one = user,
+        seeds = [b"user-mining-deposit", user.key().as_ref()],
+        bump = user_deposit.bump
+    )]
+    pub user_deposit: Account<'info, UserMiningDeposit>,
+    #[account(mut)]
+    pub user: Signer<'info>,
 }
 
 // Error Codes
@@ -291,6 +434,8 @@ pub enum MiningError {
     WithdrawalCooldown,
     #[msg("Entry fee exceeds cap")]
     EntryFeeExceedsCap,
+    #[msg("Deposit account still holds tokens")]
+    DepositNotEmpty,
 }
 
 
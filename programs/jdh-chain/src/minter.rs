@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+
+use crate::config::TokenConfig;
+
+// Minter Allowance Account
+#[account]
+pub struct MinterAllowance {
+    pub config: Pubkey,                  // Token config this minter belongs to
+    pub minter: Pubkey,                  // Minter wallet or program PDA
+    pub total_allowance: u64,            // Lifetime mint allowance
+    pub total_minted: u64,               // Lifetime minted amount
+    pub window_allowance: u64,           // Mint allowance per window
+    pub window_duration: i64,            // Window length in seconds
+    pub window_start: i64,               // Start of the current window
+    pub window_minted: u64,              // Minted in the current window
+    pub bump: u8,                        // Bump seed
+}
+
+impl MinterAllowance {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    // Debit both the lifetime and the current window allowance
+    pub fn debit(&mut self, amount: u64, now: i64) -> Result<()> {
+        let total_minted = self
+            .total_minted
+            .checked_add(amount)
+            .ok_or(MinterError::MinterAllowanceExceeded)?;
+        require!(
+            total_minted <= self.total_allowance,
+            MinterError::MinterAllowanceExceeded
+        );
+
+        if now >= self.window_start.saturating_add(self.window_duration) {
+            self.window_start = now;
+            self.window_minted = 0;
+        }
+
+        let window_minted = self
+            .window_minted
+            .checked_add(amount)
+            .ok_or(MinterError::MinterWindowAllowanceExceeded)?;
+        require!(
+            window_minted <= self.window_allowance,
+            MinterError::MinterWindowAllowanceExceeded
+        );
+
+        self.total_minted = total_minted;
+        self.window_minted = window_minted;
+        Ok(())
+    }
+}
+
+#[event]
+pub struct MinterAdded {
+    pub config: Pubkey,
+    pub minter: Pubkey,
+    pub total_allowance: u64,
+    pub window_allowance: u64,
+    pub window_duration: i64,
+}
+
+#[event]
+pub struct MinterRemoved {
+    pub config: Pubkey,
+    pub minter: Pubkey,
+    pub total_minted: u64,
+}
+
+#[event]
+pub struct TokensMinted {
+    pub mint: Pubkey,
+    pub minter: Pubkey,
+    pub to: Pubkey,
+    pub amount: u64,
+    pub minter_total_minted: u64,
+    pub total_minted: u64,
+    pub timestamp: i64,
+}
+
+// Add Minter
+pub fn add(
+    ctx: Context<AddMinter>,
+    total_allowance: u64,
+    window_allowance: u64,
+    window_duration: i64,
+) -> Result<()> {
+    require!(window_duration > 0, MinterError::InvalidMinterWindow);
+
+    let minter_allowance = &mut ctx.accounts.minter_allowance;
+    minter_allowance.config = ctx.accounts.config.key();
+    minter_allowance.minter = ctx.accounts.minter.key();
+    minter_allowance.total_allowance = total_allowance;
+    minter_allowance.total_minted = 0;
+    minter_allowance.window_allowance = window_allowance;
+    minter_allowance.window_duration = window_duration;
+    minter_allowance.window_start = Clock::get()?.unix_timestamp;
+    minter_allowance.window_minted = 0;
+    minter_allowance.bump = ctx.bumps.minter_allowance;
+
+    emit!(MinterAdded {
+        config: minter_allowance.config,
+        minter: minter_allowance.minter,
+        total_allowance,
+        window_allowance,
+        window_duration,
+    });
+    Ok(())
+}
+
+// Remove Minter (rent goes back to the authority)
+pub fn remove(ctx: Context<RemoveMinter>) -> Result<()> {
+    let minter_allowance = &ctx.accounts.minter_allowance;
+
+    emit!(MinterRemoved {
+        config: minter_allowance.config,
+        minter: minter_allowance.minter,
+        total_minted: minter_allowance.total_minted,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddMinter<'info> {
+    #[account(has_one = authority)]
+    pub config: Account<'info, TokenConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = MinterAllowance::LEN,
+        seeds = [b"minter", config.key().as_ref(), minter.key().as_ref()],
+        bump
+    )]
+    pub minter_allowance: Account<'info, MinterAllowance>,
+    /// CHECK: Only used as a seed and recorded as the minter
+    pub minter: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveMinter<'info> {
+    #[account(has_one = authority)]
+    pub config: Account<'info, TokenConfig>,
+    #[account(
+        mut,
+        close = authority,
+        has_one = config,
+        seeds = [b"minter", config.key().as_ref(), minter_allowance.minter.as_ref()],
+        bump = minter_allowance.bump
+    )]
+    pub minter_allowance: Account<'info, MinterAllowance>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[error_code]
+pub enum MinterError {
+    #[msg("Minter window duration must be positive")]
+    InvalidMinterWindow,
+    #[msg("Mint would exceed the minter's total allowance")]
+    MinterAllowanceExceeded,
+    #[msg("Mint would exceed the minter's window allowance")]
+    MinterWindowAllowanceExceeded,
+}
@@ -0,0 +1,439 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+
+pub const MAX_MULTISIG_MEMBERS: usize = 10;
+
+// M-of-N Multisig Account
+// The signer PDA [b"multisig-signer", multisig] is the key to name as a pool, vault
+// or mint authority; it only signs instructions the members approved. Fund it with
+// SOL when it has to pay rent, e.g. to initialize a pool with itself as authority.
+#[account]
+pub struct Multisig {
+    pub id: u64,                         // Multisig ID (per creator)
+    pub creator: Pubkey,                 // Creator, only used as a seed
+    pub threshold: u8,                   // Approvals required to execute
+    pub members: Vec<Pubkey>,            // Members allowed to propose and approve
+    pub members_version: u32,            // Bumped on member changes, voids pending approvals
+    pub transaction_count: u64,          // Number of proposed transactions
+    pub signer_bump: u8,                 // Bump for the signer PDA
+    pub bump: u8,                        // Bump seed
+}
+
+// Account meta of a proposed instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+// Proposed Multisig Transaction (a single instruction signed by the signer PDA)
+#[account]
+pub struct MultisigTransaction {
+    pub multisig: Pubkey,                // Owning multisig
+    pub index: u64,                      // Transaction index within the multisig
+    pub proposer: Pubkey,                // Member who proposed it
+    pub program_id: Pubkey,              // Program to invoke
+    pub accounts: Vec<TransactionAccount>, // Instruction accounts
+    pub data: Vec<u8>,                   // Instruction data
+    pub approvals: Vec<bool>,            // One flag per member
+    pub members_version: u32,            // Multisig members version at proposal time
+    pub executed: bool,                  // Whether it was executed
+    pub created_at: i64,                 // Proposal timestamp
+    pub bump: u8,                        // Bump seed
+}
+
+impl Multisig {
+    pub const LEN: usize = 8 + 8 + 32 + 1 + 4 + 32 * MAX_MULTISIG_MEMBERS + 4 + 8 + 1 + 1;
+
+    pub fn member_index(&self, key: &Pubkey) -> Option<usize> {
+        self.members.iter().position(|member| member == key)
+    }
+}
+
+impl MultisigTransaction {
+    pub fn space(num_accounts: usize, data_len: usize, num_members: usize) -> usize {
+        8 + 32 + 8 + 32 + 32
+            + 4 + num_accounts * (32 + 1 + 1)
+            + 4 + data_len
+            + 4 + num_members
+            + 4 + 1 + 8 + 1
+    }
+
+    pub fn approval_count(&self) -> usize {
+        self.approvals.iter().filter(|approved| **approved).count()
+    }
+}
+
+fn validate_members(members: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !members.is_empty() && members.len() <= MAX_MULTISIG_MEMBERS,
+        MultisigError::InvalidMembers
+    );
+    for (i, member) in members.iter().enumerate() {
+        require!(
+            !members[..i].contains(member),
+            MultisigError::DuplicateMember
+        );
+    }
+    require!(
+        threshold > 0 && threshold as usize <= members.len(),
+        MultisigError::InvalidThreshold
+    );
+    Ok(())
+}
+
+#[event]
+pub struct MultisigCreated {
+    pub multisig: Pubkey,
+    pub signer: Pubkey,
+    pub threshold: u8,
+    pub members: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultisigMembersChanged {
+    pub multisig: Pubkey,
+    pub threshold: u8,
+    pub members: Vec<Pubkey>,
+    pub members_version: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultisigTransactionProposed {
+    pub multisig: Pubkey,
+    pub transaction: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub program_id: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultisigTransactionApproved {
+    pub multisig: Pubkey,
+    pub transaction: Pubkey,
+    pub member: Pubkey,
+    pub approvals: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultisigTransactionExecuted {
+    pub multisig: Pubkey,
+    pub transaction: Pubkey,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+// Create Multisig
+pub(crate) fn create(
+    ctx: Context<CreateMultisig>,
+    id: u64,
+    members: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    validate_members(&members, threshold)?;
+
+    let multisig = &mut ctx.accounts.multisig;
+    multisig.id = id;
+    multisig.creator = ctx.accounts.creator.key();
+    multisig.threshold = threshold;
+    multisig.members = members;
+    multisig.members_version = 0;
+    multisig.transaction_count = 0;
+    multisig.signer_bump = ctx.bumps.multisig_signer;
+    multisig.bump = ctx.bumps.multisig;
+
+    emit!(MultisigCreated {
+        multisig: multisig.key(),
+        signer: ctx.accounts.multisig_signer.key(),
+        threshold,
+        members: multisig.members.clone(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Propose an instruction for the multisig to sign (member), counts as their approval
+pub(crate) fn propose(
+    ctx: Context<ProposeMultisigTransaction>,
+    program_id: Pubkey,
+    accounts: Vec<TransactionAccount>,
+    data: Vec<u8>,
+) -> Result<()> {
+    let multisig = &mut ctx.accounts.multisig;
+    let proposer = ctx.accounts.proposer.key();
+    let member_index = multisig
+        .member_index(&proposer)
+        .ok_or(MultisigError::NotAMember)?;
+
+    let mut approvals = vec![false; multisig.members.len()];
+    approvals[member_index] = true;
+
+    let transaction = &mut ctx.accounts.transaction;
+    transaction.multisig = multisig.key();
+    transaction.index = multisig.transaction_count;
+    transaction.proposer = proposer;
+    transaction.program_id = program_id;
+    transaction.accounts = accounts;
+    transaction.data = data;
+    transaction.approvals = approvals;
+    transaction.members_version = multisig.members_version;
+    transaction.executed = false;
+    transaction.created_at = Clock::get()?.unix_timestamp;
+    transaction.bump = ctx.bumps.transaction;
+
+    multisig.transaction_count += 1;
+
+    emit!(MultisigTransactionProposed {
+        multisig: transaction.multisig,
+        transaction: transaction.key(),
+        index: transaction.index,
+        proposer,
+        program_id,
+        timestamp: transaction.created_at,
+    });
+    Ok(())
+}
+
+// Approve a proposed transaction (member)
+pub(crate) fn approve(ctx: Context<ApproveMultisigTransaction>) -> Result<()> {
+    let multisig = &ctx.accounts.multisig;
+    let transaction = &mut ctx.accounts.transaction;
+    let member = ctx.accounts.member.key();
+
+    require!(!transaction.executed, MultisigError::AlreadyExecuted);
+    require!(
+        transaction.members_version == multisig.members_version,
+        MultisigError::MembersChanged
+    );
+    let member_index = multisig
+        .member_index(&member)
+        .ok_or(MultisigError::NotAMember)?;
+    require!(
+        !transaction.approvals[member_index],
+        MultisigError::AlreadyApproved
+    );
+
+    transaction.approvals[member_index] = true;
+
+    emit!(MultisigTransactionApproved {
+        multisig: multisig.key(),
+        transaction: transaction.key(),
+        member,
+        approvals: transaction.approval_count() as u8,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Execute a transaction once it has threshold approvals (member)
+// Every account of the proposed instruction, and its program, go in remaining_accounts
+pub(crate) fn execute<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteMultisigTransaction<'info>>,
+) -> Result<()> {
+    let multisig = &ctx.accounts.multisig;
+    let transaction = &mut ctx.accounts.transaction;
+    let executor = ctx.accounts.executor.key();
+
+    require!(
+        multisig.member_index(&executor).is_some(),
+        MultisigError::NotAMember
+    );
+    require!(!transaction.executed, MultisigError::AlreadyExecuted);
+    require!(
+        transaction.members_version == multisig.members_version,
+        MultisigError::MembersChanged
+    );
+    require!(
+        transaction.approval_count() >= multisig.threshold as usize,
+        MultisigError::NotEnoughApprovals
+    );
+
+    // Persist the executed flag before the CPI so the instruction cannot re-enter itself
+    transaction.executed = true;
+    transaction.exit(&crate::ID)?;
+
+    let multisig_signer = ctx.accounts.multisig_signer.key();
+    let instruction = Instruction {
+        program_id: transaction.program_id,
+        accounts: transaction
+            .accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.pubkey,
+                // The signer PDA is signed for by this program
+                is_signer: account.is_signer || account.pubkey == multisig_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: transaction.data.clone(),
+    };
+
+    let multisig_key = multisig.key();
+    invoke_signed(
+        &instruction,
+        ctx.remaining_accounts,
+        &[&[
+            b"multisig-signer",
+            multisig_key.as_ref(),
+            &[multisig.signer_bump],
+        ]],
+    )?;
+
+    emit!(MultisigTransactionExecuted {
+        multisig: multisig_key,
+        transaction: transaction.key(),
+        executor,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Replace members and threshold (signer PDA only, i.e. through an executed transaction)
+pub(crate) fn set_members(
+    ctx: Context<SetMultisigMembers>,
+    members: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    validate_members(&members, threshold)?;
+
+    let multisig = &mut ctx.accounts.multisig;
+    multisig.members = members;
+    multisig.threshold = threshold;
+    multisig.members_version += 1;
+
+    emit!(MultisigMembersChanged {
+        multisig: multisig.key(),
+        threshold,
+        members: multisig.members.clone(),
+        members_version: multisig.members_version,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateMultisig<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = Multisig::LEN,
+        seeds = [b"multisig", creator.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    /// CHECK: PDA signer for approved transactions, holds no data
+    #[account(seeds = [b"multisig-signer", multisig.key().as_ref()], bump)]
+    pub multisig_signer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(program_id: Pubkey, accounts: Vec<TransactionAccount>, data: Vec<u8>)]
+pub struct ProposeMultisigTransaction<'info> {
+    #[account(
+        mut,
+        seeds = [b"multisig", multisig.creator.as_ref(), multisig.id.to_le_bytes().as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        init,
+        payer = proposer,
+        space = MultisigTransaction::space(accounts.len(), data.len(), multisig.members.len()),
+        seeds = [
+            b"multisig-tx",
+            multisig.key().as_ref(),
+            multisig.transaction_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transaction: Account<'info, MultisigTransaction>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveMultisigTransaction<'info> {
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        mut,
+        has_one = multisig,
+        seeds = [
+            b"multisig-tx",
+            multisig.key().as_ref(),
+            transaction.index.to_le_bytes().as_ref()
+        ],
+        bump = transaction.bump
+    )]
+    pub transaction: Account<'info, MultisigTransaction>,
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteMultisigTransaction<'info> {
+    // Not mut: the executed instruction may itself update the multisig
+    pub multisig: Account<'info, Multisig>,
+    /// CHECK: PDA signer for approved transactions, holds no data
+    #[account(
+        seeds = [b"multisig-signer", multisig.key().as_ref()],
+        bump = multisig.signer_bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        has_one = multisig,
+        seeds = [
+            b"multisig-tx",
+            multisig.key().as_ref(),
+            transaction.index.to_le_bytes().as_ref()
+        ],
+        bump = transaction.bump
+    )]
+    pub transaction: Account<'info, MultisigTransaction>,
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMultisigMembers<'info> {
+    #[account(
+        mut,
+        seeds = [b"multisig", multisig.creator.as_ref(), multisig.id.to_le_bytes().as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+    #[account(
+        seeds = [b"multisig-signer", multisig.key().as_ref()],
+        bump = multisig.signer_bump
+    )]
+    pub multisig_signer: Signer<'info>,
+}
+
+#[error_code]
+pub enum MultisigError {
+    #[msg("Members must be non-empty and within the maximum")]
+    InvalidMembers,
+    #[msg("Duplicate multisig member")]
+    DuplicateMember,
+    #[msg("Threshold must be between 1 and the number of members")]
+    InvalidThreshold,
+    #[msg("Signer is not a multisig member")]
+    NotAMember,
+    #[msg("Member already approved this transaction")]
+    AlreadyApproved,
+    #[msg("Transaction already executed")]
+    AlreadyExecuted,
+    #[msg("Multisig members changed since the transaction was proposed")]
+    MembersChanged,
+    #[msg("Not enough approvals to execute")]
+    NotEnoughApprovals,
+}
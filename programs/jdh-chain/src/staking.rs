@@ -1,79 +1,376 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::config::{PauseError, ProgramConfig};
+use crate::transfer::transfer_in;
+
+// Fixed-point scale for acc_reward_per_share
+pub const ACC_REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+// Lock options a pool can offer, e.g. 30, 90, 180 and 365 days
+pub const MAX_LOCK_TIERS: usize = 8;
+
+// Ceiling on a lock tier's reward multiplier (5x)
+pub const MAX_LOCK_MULTIPLIER_BPS: u16 = 50_000;
+
+// Ceiling on the share of compounded rewards paid to a third-party cranker (5%)
+pub const MAX_COMPOUND_TIP_BPS: u16 = 500;
+
+// Lock Tier: longer locks earn a larger share of the emission
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct LockTier {
+    pub lock_period: i64,       // Lock period in seconds
+    pub multiplier_bps: u16,    // Reward weight in basis points (10000 = 1x)
+}
+
+impl LockTier {
+    pub fn weight(&self, amount: u64) -> Result<u64> {
+        u64::try_from(amount as u128 * self.multiplier_bps as u128 / 10_000)
+            .map_err(|_| error!(StakingError::WeightOverflow))
+    }
+}
 
 // Staking Pool Account
+// Rewards are emitted at reward_rate per second and shared pro rata to stake
+// weight (amount boosted by the lock tier) through acc_reward_per_share (MasterChef style).
+// Emission only runs until reward_end, the point where the funded budget runs out.
 #[account]
 pub struct StakingPool {
     pub mint: Pubkey,           // JDH Token mint
     pub total_staked: u64,      // Total staked amount
-    pub apy: u16,               // APY in basis points (e.g., 1200 = 12%)
+    pub total_weight: u64,      // Total tier-weighted stake
+    pub reward_rate: u64,       // Rewards emitted per second to the whole pool
+    pub acc_reward_per_share: u128, // Rewards per unit of weight, scaled by ACC_REWARD_PRECISION
+    pub last_update: i64,       // Last time acc_reward_per_share was updated
+    pub reward_end: i64,        // Emission stops here, when remaining_rewards runs out
+    pub remaining_rewards: u64, // Funded rewards not emitted yet
     pub min_stake: u64,         // Minimum stake amount
-    pub lock_period: i64,       // Lock period in seconds
-    pub pool_authority: Pubkey,  // Pool authority
+    pub stake_vault: Pubkey,    // PDA token account holding staked tokens
+    pub rewards_vault: Pubkey,  // PDA token account holding rewards
+    pub vault_authority_bump: u8, // Bump for the pool-authority PDA owning both vaults
+    pub pool_authority: Pubkey,  // Pool authority (default = renounced)
+    pub pending_authority: Option<Pubkey>, // Proposed authority, must accept
+    pub bump: u8,               // Bump seed
+    pub lock_tiers: Vec<LockTier>, // Lock options offered by the pool
+    pub compound_tip_bps: u16,  // Cut of compounded rewards paid to whoever cranks it
+}
+
+// User Stake Counter (one per user per pool, lets wallets enumerate positions)
+#[account]
+pub struct UserStakeCounter {
+    pub user: Pubkey,           // User wallet
+    pub pool: Pubkey,           // Staking pool
+    pub position_count: u64,    // Position ids handed out, positions are 0..position_count
+    pub open_positions: u64,    // Positions not closed yet
     pub bump: u8,               // Bump seed
 }
 
-// User Staking Account
+// User Stake Position (indexed, each with its own amount, lock and rewards)
 #[account]
 pub struct UserStake {
     pub user: Pubkey,           // User wallet
     pub pool: Pubkey,           // Staking pool
+    pub position_id: u64,       // Position index for this user and pool
     pub amount: u64,             // Staked amount
-    pub staked_at: i64,         // Timestamp when staked
+    pub tier: u8,               // Index into pool.lock_tiers
+    pub weight: u64,            // amount boosted by the tier multiplier
     pub lock_until: i64,        // Timestamp when lock expires
     pub rewards_earned: u64,    // Total rewards earned
+    pub pending_rewards: u64,   // Rewards settled at the last checkpoint, not yet paid
+    pub reward_debt: u128,      // weight * acc_reward_per_share already accounted for
     pub bump: u8,               // Bump seed
+    pub auto_compound: bool,    // Anyone may compound this position for the pool tip
 }
 
 impl StakingPool {
-    pub const LEN: usize = 8 + 32 + 8 + 2 + 8 + 8 + 32 + 1;
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 16 + 8 + 8 + 8 + 8 + 32 + 32 + 1 + 32 + (1 + 32) + 1
+        + 4 + MAX_LOCK_TIERS * (8 + 2) + 2;
+
+    // Accrue rewards emitted since the last update into acc_reward_per_share,
+    // never past reward_end or beyond the funded budget
+    pub fn update(&mut self, now: i64) {
+        if now <= self.last_update {
+            return;
+        }
+        let end = now.min(self.reward_end);
+        if end > self.last_update {
+            if self.total_weight > 0 {
+                let elapsed = (end - self.last_update) as u128;
+                let emitted =
+                    (elapsed * self.reward_rate as u128).min(self.remaining_rewards as u128);
+                let increment = emitted * ACC_REWARD_PRECISION / self.total_weight as u128;
+                // Only what the rounded-down increment hands out leaves the budget,
+                // the dust stays in remaining_rewards
+                let distributed = increment * self.total_weight as u128 / ACC_REWARD_PRECISION;
+                self.acc_reward_per_share += increment;
+                self.remaining_rewards -= distributed as u64;
+            } else {
+                // Nobody staked: nothing is emitted, so the window slides by the idle time
+                // and the unspent budget is still paid out to whoever stakes next
+                self.reward_end = self.reward_end.saturating_add(now - self.last_update);
+            }
+        }
+        self.last_update = now;
+    }
+
+    // Stretch the remaining budget from now at the current rate (pool must be updated first)
+    pub fn reset_reward_window(&mut self, now: i64) {
+        self.reward_end = match self.remaining_rewards.checked_div(self.reward_rate) {
+            Some(seconds) => now.saturating_add(seconds.min(i64::MAX as u64) as i64),
+            None => now,
+        };
+    }
+}
+
+impl UserStakeCounter {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1;
 }
 
 impl UserStake {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 16 + 1 + 1;
+
+    // Settle rewards accrued on the current weight into pending_rewards
+    // (pool must be updated first, weight must not have changed since the last checkpoint)
+    pub fn checkpoint(&mut self, pool: &StakingPool) {
+        let accrued = self.weight as u128 * pool.acc_reward_per_share / ACC_REWARD_PRECISION;
+        self.pending_rewards += (accrued - self.reward_debt) as u64;
+        self.reward_debt = accrued;
+    }
+
+    // Re-weight after amount changed (right after a checkpoint), keeping the pool total in sync
+    pub fn reweigh(&mut self, pool: &mut StakingPool) -> Result<()> {
+        let weight = pool.lock_tiers[self.tier as usize].weight(self.amount)?;
+        pool.total_weight = pool
+            .total_weight
+            .checked_sub(self.weight)
+            .and_then(|total| total.checked_add(weight))
+            .ok_or(StakingError::WeightOverflow)?;
+        self.weight = weight;
+        self.reward_debt = weight as u128 * pool.acc_reward_per_share / ACC_REWARD_PRECISION;
+        Ok(())
+    }
+
+    // Pay out as much of pending_rewards as `available` covers and return the amount paid;
+    // the rest stays pending to claim later
+    pub fn pay_rewards(&mut self, available: u64) -> u64 {
+        let paid = self.pending_rewards.min(available);
+        self.pending_rewards -= paid;
+        self.rewards_earned += paid;
+        paid
+    }
+
+    // Take principal out of the position (right after a checkpoint)
+    pub fn withdraw(&mut self, pool: &mut StakingPool, amount: u64) -> Result<()> {
+        self.amount -= amount;
+        pool.total_staked -= amount;
+        self.reweigh(pool)
+    }
+}
+
+#[event]
+pub struct StakingPoolInitialized {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub pool_authority: Pubkey,
+    pub stake_vault: Pubkey,
+    pub rewards_vault: Pubkey,
+    pub reward_rate: u64,
+    pub min_stake: u64,
+    pub lock_tiers: Vec<LockTier>,
+    pub timestamp: i64,
 }
 
-// Stake JDH Tokens
-pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+#[event]
+pub struct Staked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub position_id: u64,
+    pub amount: u64,
+    pub tier: u8,
+    pub user_staked: u64,
+    pub pool_total_staked: u64,
+    pub lock_until: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub position_id: u64,
+    pub amount: u64,
+    pub rewards: u64,
+    pub pending_rewards: u64,
+    pub user_staked: u64,
+    pub pool_total_staked: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StakingRewardsClaimed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub position_id: u64,
+    pub amount: u64,
+    pub rewards_earned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardRateUpdated {
+    pub pool: Pubkey,
+    pub reward_rate: u64,
+    pub acc_reward_per_share: u128,
+    pub reward_end: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsFunded {
+    pub pool: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub remaining_rewards: u64,
+    pub reward_end: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsCompounded {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub position_id: u64,
+    pub cranker: Pubkey,
+    pub amount: u64,
+    pub tip: u64,
+    pub user_staked: u64,
+    pub pool_total_staked: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AutoCompoundSet {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub position_id: u64,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CompoundTipUpdated {
+    pub pool: Pubkey,
+    pub compound_tip_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserStakeClosed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub position_id: u64,
+    pub rewards_earned: u64,
+    pub timestamp: i64,
+}
+
+// Validate a pool's lock tier table
+pub fn validate_lock_tiers(lock_tiers: &[LockTier]) -> Result<()> {
+    require!(
+        !lock_tiers.is_empty() && lock_tiers.len() <= MAX_LOCK_TIERS,
+        StakingError::InvalidLockTiers
+    );
+    for tier in lock_tiers {
+        require!(
+            tier.lock_period >= 0
+                && tier.multiplier_bps > 0
+                && tier.multiplier_bps <= MAX_LOCK_MULTIPLIER_BPS,
+            StakingError::InvalidLockTiers
+        );
+    }
+    Ok(())
+}
+
+// Stake JDH Tokens into a position, position_count opens a new one (top-ups must keep the tier)
+pub fn stake(ctx: Context<Stake>, position_id: u64, amount: u64, tier: u8) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
+    let counter = &mut ctx.accounts.counter;
     let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    // Closed positions are not reopened, their ids stay retired
+    let opening = position_id == counter.position_count;
+    require!(
+        opening || (position_id < counter.position_count && user_stake.user != Pubkey::default()),
+        StakingError::InvalidPositionId
+    );
+    if opening {
+        // Opening a new position, counter may have just been initialized
+        counter.user = ctx.accounts.user.key();
+        counter.pool = pool.key();
+        counter.position_count += 1;
+        counter.open_positions += 1;
+        counter.bump = ctx.bumps.counter;
+    }
 
     // Check minimum stake
     require!(
         amount >= pool.min_stake,
-        JdhChainError::InsufficientStakeAmount
+        StakingError::InsufficientStakeAmount
+    );
+    let lock_period = pool
+        .lock_tiers
+        .get(tier as usize)
+        .ok_or(StakingError::InvalidTier)?
+        .lock_period;
+    require!(
+        user_stake.amount == 0 || user_stake.tier == tier,
+        StakingError::TierMismatch
     );
 
-    // Transfer tokens to staking pool
-    token::transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user_token_account.to_account_info(),
-                to: ctx.accounts.pool_token_account.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            },
-        ),
+    // Settle what the existing stake earned before it changes
+    pool.update(clock.unix_timestamp);
+    user_stake.checkpoint(pool);
+
+    // Transfer tokens to the stake vault
+    let received = transfer_in(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.user_token_account.to_account_info(),
+        &mut ctx.accounts.stake_vault,
+        ctx.accounts.user.to_account_info(),
+        &[],
         amount,
     )?;
 
     // Update user stake
-    let clock = Clock::get()?;
     if user_stake.amount == 0 {
         // First time staking
         user_stake.user = ctx.accounts.user.key();
         user_stake.pool = pool.key();
-        user_stake.staked_at = clock.unix_timestamp;
-        user_stake.lock_until = clock.unix_timestamp + pool.lock_period;
+        user_stake.position_id = position_id;
+        user_stake.tier = tier;
+        user_stake.lock_until = clock.unix_timestamp + lock_period;
+        user_stake.bump = ctx.bumps.user_stake;
     } else {
         // Additional stake
-        user_stake.lock_until = clock.unix_timestamp + pool.lock_period;
+        user_stake.lock_until = clock.unix_timestamp + lock_period;
     }
 
-    user_stake.amount += amount;
-    pool.total_staked += amount;
+    user_stake.amount += received;
+    pool.total_staked += received;
+    user_stake.reweigh(pool)?;
 
-    msg!("Staked {} JDH tokens", amount);
+    emit!(Staked {
+        pool: pool.key(),
+        user: user_stake.user,
+        position_id: user_stake.position_id,
+        amount: received,
+        tier,
+        user_staked: user_stake.amount,
+        pool_total_staked: pool.total_staked,
+        lock_until: user_stake.lock_until,
+        timestamp: clock.unix_timestamp,
+    });
     Ok(())
 }
 
@@ -85,195 +382,573 @@ pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
     // Check if user has enough staked
     require!(
         user_stake.amount >= amount,
-        JdhChainError::InsufficientStakedAmount
+        StakingError::InsufficientStakedAmount
     );
 
     // Check lock period
     let clock = Clock::get()?;
     require!(
         clock.unix_timestamp >= user_stake.lock_until,
-        JdhChainError::LockPeriodNotExpired
+        StakingError::LockPeriodNotExpired
     );
 
-    // Calculate and distribute rewards
-    let rewards = calculate_rewards(user_stake, pool, &clock)?;
-    
+    // Settle and pay out what the rewards vault can cover; principal is returned regardless
+    // and any shortfall stays in pending_rewards to claim later
+    pool.update(clock.unix_timestamp);
+    user_stake.checkpoint(pool);
+    let rewards = user_stake.pay_rewards(ctx.accounts.rewards_vault.amount);
+
     if rewards > 0 {
         // Transfer rewards
-        token::transfer(
+        token_interface::transfer_checked(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
+                TransferChecked {
                     from: ctx.accounts.rewards_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
                     to: ctx.accounts.user_token_account.to_account_info(),
-                    authority: ctx.accounts.pool_authority.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info(),
                 },
                 &[&[
                     b"pool-authority",
                     pool.key().as_ref(),
-                    &[pool.bump],
+                    &[pool.vault_authority_bump],
                 ]],
             ),
             rewards,
+            ctx.accounts.mint.decimals,
         )?;
-
-        user_stake.rewards_earned += rewards;
     }
 
     // Transfer staked tokens back
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.pool_token_account.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.pool_authority.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
             },
             &[&[
                 b"pool-authority",
                 pool.key().as_ref(),
-                &[pool.bump],
+                &[pool.vault_authority_bump],
             ]],
         ),
         amount,
+        ctx.accounts.mint.decimals,
     )?;
 
-    user_stake.amount -= amount;
-    pool.total_staked -= amount;
+    user_stake.withdraw(pool, amount)?;
 
-    msg!("Unstaked {} JDH tokens, earned {} rewards", amount, rewards);
+    emit!(Unstaked {
+        pool: pool.key(),
+        user: user_stake.user,
+        position_id: user_stake.position_id,
+        amount,
+        rewards,
+        pending_rewards: user_stake.pending_rewards,
+        user_staked: user_stake.amount,
+        pool_total_staked: pool.total_staked,
+        timestamp: clock.unix_timestamp,
+    });
     Ok(())
 }
 
 // Claim Rewards
 pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
-    let pool = &ctx.accounts.pool;
+    let pool = &mut ctx.accounts.pool;
     let user_stake = &mut ctx.accounts.user_stake;
 
     let clock = Clock::get()?;
-    let rewards = calculate_rewards(user_stake, pool, &clock)?;
+    pool.update(clock.unix_timestamp);
+    user_stake.checkpoint(pool);
+    let rewards = user_stake.pending_rewards;
 
-    require!(rewards > 0, JdhChainError::NoRewardsAvailable);
+    require!(rewards > 0, StakingError::NoRewardsAvailable);
 
     // Transfer rewards
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.rewards_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.pool_authority.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
             },
             &[&[
                 b"pool-authority",
                 pool.key().as_ref(),
-                &[pool.bump],
+                &[pool.vault_authority_bump],
             ]],
         ),
         rewards,
+        ctx.accounts.mint.decimals,
     )?;
 
     user_stake.rewards_earned += rewards;
-    user_stake.staked_at = clock.unix_timestamp; // Reset for next period
+    user_stake.pending_rewards = 0;
 
-    msg!("Claimed {} JDH rewards", rewards);
+    emit!(StakingRewardsClaimed {
+        pool: pool.key(),
+        user: user_stake.user,
+        position_id: user_stake.position_id,
+        amount: rewards,
+        rewards_earned: user_stake.rewards_earned,
+        timestamp: clock.unix_timestamp,
+    });
     Ok(())
 }
 
-// Calculate rewards based on APY and time staked
-fn calculate_rewards(
-    user_stake: &UserStake,
-    pool: &StakingPool,
-    clock: &Clock,
-) -> Result<u64> {
-    let time_staked = clock.unix_timestamp - user_stake.staked_at;
-    let seconds_per_year = 365 * 24 * 60 * 60;
-    
-    // Calculate: amount * (APY / 10000) * (time_staked / seconds_per_year)
-    let apy_decimal = pool.apy as u128;
-    let amount = user_stake.amount as u128;
-    let time = time_staked as u128;
-    let seconds = seconds_per_year as u128;
-    
-    let rewards = (amount * apy_decimal * time) / (10000 * seconds);
-    
-    Ok(rewards as u64)
+// Set the pool's reward emission rate (pool authority), accruing at the old rate first
+pub fn update_reward_rate(ctx: Context<SetRewardRate>, reward_rate: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let clock = Clock::get()?;
+
+    pool.update(clock.unix_timestamp);
+    pool.reward_rate = reward_rate;
+    pool.reset_reward_window(clock.unix_timestamp);
+
+    emit!(RewardRateUpdated {
+        pool: pool.key(),
+        reward_rate,
+        acc_reward_per_share: pool.acc_reward_per_share,
+        reward_end: pool.reward_end,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Add rewards to the pool's budget (anyone), extending the emission window
+pub fn fund(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+    require!(amount > 0, StakingError::InvalidFundingAmount);
+
+    let clock = Clock::get()?;
+    ctx.accounts.pool.update(clock.unix_timestamp);
+
+    let received = transfer_in(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.funder_token_account.to_account_info(),
+        &mut ctx.accounts.rewards_vault,
+        ctx.accounts.funder.to_account_info(),
+        &[],
+        amount,
+    )?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.remaining_rewards += received;
+    pool.reset_reward_window(clock.unix_timestamp);
+
+    emit!(RewardsFunded {
+        pool: pool.key(),
+        funder: ctx.accounts.funder.key(),
+        amount: received,
+        remaining_rewards: pool.remaining_rewards,
+        reward_end: pool.reward_end,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Restake pending rewards into the position's principal (owner, or anyone if auto-compound is on)
+// A third-party cranker keeps compound_tip_bps of the rewards, the lock is not extended
+pub fn compound(ctx: Context<CompoundRewards>) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let cranker = ctx.accounts.cranker.key();
+
+    let tip_bps = if cranker == user_stake.user {
+        0
+    } else {
+        require!(user_stake.auto_compound, StakingError::AutoCompoundDisabled);
+        pool.compound_tip_bps
+    };
+
+    let clock = Clock::get()?;
+    pool.update(clock.unix_timestamp);
+    user_stake.checkpoint(pool);
+    let rewards = user_stake.pending_rewards;
+
+    require!(rewards > 0, StakingError::NoRewardsAvailable);
+
+    let tip = (rewards as u128 * tip_bps as u128 / 10_000) as u64;
+    let restaked = rewards - tip;
+    let pool_key = pool.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"pool-authority",
+        pool_key.as_ref(),
+        &[pool.vault_authority_bump],
+    ]];
+
+    if tip > 0 {
+        // Pay the cranker
+        let cranker_token_account = ctx
+            .accounts
+            .cranker_token_account
+            .as_ref()
+            .ok_or(StakingError::MissingCrankerTokenAccount)?;
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.rewards_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: cranker_token_account.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            tip,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    // Move the rest into the stake vault as principal
+    let received = transfer_in(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.rewards_vault.to_account_info(),
+        &mut ctx.accounts.stake_vault,
+        ctx.accounts.vault_authority.to_account_info(),
+        signer_seeds,
+        restaked,
+    )?;
+
+    user_stake.rewards_earned += rewards;
+    user_stake.pending_rewards = 0;
+    user_stake.amount += received;
+    pool.total_staked += received;
+    user_stake.reweigh(pool)?;
+
+    emit!(RewardsCompounded {
+        pool: pool_key,
+        user: user_stake.user,
+        position_id: user_stake.position_id,
+        cranker,
+        amount: received,
+        tip,
+        user_staked: user_stake.amount,
+        pool_total_staked: pool.total_staked,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Opt a position in or out of permissionless compounding (position owner)
+pub fn toggle_auto_compound(ctx: Context<SetAutoCompound>, enabled: bool) -> Result<()> {
+    let user_stake = &mut ctx.accounts.user_stake;
+    user_stake.auto_compound = enabled;
+
+    emit!(AutoCompoundSet {
+        pool: user_stake.pool,
+        user: user_stake.user,
+        position_id: user_stake.position_id,
+        enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Set the cranker tip for compounding other users' positions (pool authority)
+pub fn update_compound_tip(ctx: Context<SetRewardRate>, compound_tip_bps: u16) -> Result<()> {
+    require!(
+        compound_tip_bps <= MAX_COMPOUND_TIP_BPS,
+        StakingError::CompoundTipTooHigh
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    pool.compound_tip_bps = compound_tip_bps;
+
+    emit!(CompoundTipUpdated {
+        pool: pool.key(),
+        compound_tip_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Close an empty stake account, rent goes back to the user
+pub fn close_stake(ctx: Context<CloseUserStake>) -> Result<()> {
+    let user_stake = &ctx.accounts.user_stake;
+
+    require!(
+        user_stake.amount == 0 && user_stake.pending_rewards == 0,
+        StakingError::StakeNotEmpty
+    );
+
+    ctx.accounts.counter.open_positions -= 1;
+
+    emit!(UserStakeClosed {
+        pool: user_stake.pool,
+        user: user_stake.user,
+        position_id: user_stake.position_id,
+        rewards_earned: user_stake.rewards_earned,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
 }
 
 #[derive(Accounts)]
+#[instruction(position_id: u64)]
 pub struct Stake<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"staking-pool", mint.key().as_ref()],
+        bump = pool.bump,
+        has_one = mint
+    )]
     pub pool: Account<'info, StakingPool>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserStakeCounter::LEN,
+        seeds = [b"user-stake-counter", user.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, UserStakeCounter>,
     #[account(
         init_if_needed,
         payer = user,
         space = UserStake::LEN,
-        seeds = [b"user-stake", user.key().as_ref(), pool.key().as_ref()],
+        seeds = [
+            b"user-stake",
+            user.key().as_ref(),
+            pool.key().as_ref(),
+            position_id.to_le_bytes().as_ref()
+        ],
         bump
     )]
     pub user_stake: Account<'info, UserStake>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub pool_token_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = pool.stake_vault)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.staking_paused @ PauseError::StakingPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 #[derive(Accounts)]
 pub struct Unstake<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"staking-pool", mint.key().as_ref()],
+        bump = pool.bump,
+        has_one = mint
+    )]
     pub pool: Account<'info, StakingPool>,
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
-        seeds = [b"user-stake", user.key().as_ref(), pool.key().as_ref()],
-        bump
+        seeds = [
+            b"user-stake",
+            user.key().as_ref(),
+            pool.key().as_ref(),
+            user_stake.position_id.to_le_bytes().as_ref()
+        ],
+        bump = user_stake.bump
     )]
     pub user_stake: Account<'info, UserStake>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub pool_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub rewards_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = pool.stake_vault)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = pool.rewards_vault)]
+    pub rewards_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA signer owning the pool vaults, holds no data
     #[account(
-        seeds = [b"staking-pool", pool.mint.as_ref()],
-        bump
+        seeds = [b"pool-authority", pool.key().as_ref()],
+        bump = pool.vault_authority_bump
     )]
-    pub pool_authority: Account<'info, StakingPool>,
+    pub vault_authority: UncheckedAccount<'info>,
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.staking_paused @ PauseError::StakingPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 #[derive(Accounts)]
 pub struct ClaimRewards<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"staking-pool", mint.key().as_ref()],
+        bump = pool.bump,
+        has_one = mint
+    )]
     pub pool: Account<'info, StakingPool>,
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
-        seeds = [b"user-stake", user.key().as_ref(), pool.key().as_ref()],
-        bump
+        seeds = [
+            b"user-stake",
+            user.key().as_ref(),
+            pool.key().as_ref(),
+            user_stake.position_id.to_le_bytes().as_ref()
+        ],
+        bump = user_stake.bump
     )]
     pub user_stake: Account<'info, UserStake>,
+    #[account(mut, token::mint = mint)]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = pool.rewards_vault)]
+    pub rewards_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA signer owning the pool vaults, holds no data
+    #[account(
+        seeds = [b"pool-authority", pool.key().as_ref()],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
     #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub rewards_vault: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
     #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.staking_paused @ PauseError::StakingPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardRate<'info> {
+    #[account(
+        mut,
         seeds = [b"staking-pool", pool.mint.as_ref()],
-        bump
+        bump = pool.bump,
+        has_one = pool_authority
     )]
-    pub pool_authority: Account<'info, StakingPool>,
+    pub pool: Account<'info, StakingPool>,
+    pub pool_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking-pool", mint.key().as_ref()],
+        bump = pool.bump,
+        has_one = mint
+    )]
+    pub pool: Account<'info, StakingPool>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = pool.rewards_vault)]
+    pub rewards_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub funder: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.staking_paused @ PauseError::StakingPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CompoundRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking-pool", mint.key().as_ref()],
+        bump = pool.bump,
+        has_one = mint
+    )]
+    pub pool: Account<'info, StakingPool>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [
+            b"user-stake",
+            user_stake.user.as_ref(),
+            pool.key().as_ref(),
+            user_stake.position_id.to_le_bytes().as_ref()
+        ],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    #[account(mut, address = pool.stake_vault)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = pool.rewards_vault)]
+    pub rewards_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA signer owning the pool vaults, holds no data
+    #[account(
+        seeds = [b"pool-authority", pool.key().as_ref()],
+        bump = pool.vault_authority_bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    // Only needed when a third party cranks and the pool pays a tip
+    #[account(mut, token::mint = mint)]
+    pub cranker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub cranker: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.staking_paused @ PauseError::StakingPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetAutoCompound<'info> {
+    #[account(
+        mut,
+        has_one = user,
+        seeds = [
+            b"user-stake",
+            user.key().as_ref(),
+            user_stake.pool.as_ref(),
+            user_stake.position_id.to_le_bytes().as_ref()
+        ],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseUserStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"user-stake-counter", user.key().as_ref(), user_stake.pool.as_ref()],
+        bump = counter.bump
+    )]
+    pub counter: Account<'info, UserStakeCounter>,
+    #[account(
+        mut,
+        close = user,
+        has_one = user,
+        seeds = [
+            b"user-stake",
+            user.key().as_ref(),
+            user_stake.pool.as_ref(),
+            user_stake.position_id.to_le_bytes().as_ref()
+        ],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
     #[account(mut)]
     pub user: Signer<'info>,
-    pub token_program: Program<'info, Token>,
 }
 
 #[error_code]
-pub enum JdhChainError {
+pub enum StakingError {
     #[msg("Insufficient stake amount")]
     InsufficientStakeAmount,
     #[msg("Insufficient staked amount")]
@@ -282,4 +957,264 @@ pub enum JdhChainError {
     LockPeriodNotExpired,
     #[msg("No rewards available")]
     NoRewardsAvailable,
+    #[msg("Stake account still holds tokens or unpaid rewards")]
+    StakeNotEmpty,
+    #[msg("Pool needs between 1 and 8 lock tiers with a multiplier of at most 5x")]
+    InvalidLockTiers,
+    #[msg("Lock tier does not exist")]
+    InvalidTier,
+    #[msg("Top-ups must use the stake's existing lock tier")]
+    TierMismatch,
+    #[msg("Position id must be an existing position or the next one")]
+    InvalidPositionId,
+    #[msg("Position has not opted in to auto-compounding")]
+    AutoCompoundDisabled,
+    #[msg("Compound tip exceeds the maximum")]
+    CompoundTipTooHigh,
+    #[msg("Funding amount must be positive")]
+    InvalidFundingAmount,
+    #[msg("Cranker token account is required to receive the compound tip")]
+    MissingCrankerTokenAccount,
+    #[msg("Stake weight overflow")]
+    WeightOverflow,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_X: LockTier = LockTier {
+        lock_period: 0,
+        multiplier_bps: 10_000,
+    };
+
+    // Pool created at t = 0 with `remaining_rewards` funded at `reward_rate`
+    fn pool(reward_rate: u64, remaining_rewards: u64) -> StakingPool {
+        let mut pool = StakingPool {
+            mint: Pubkey::default(),
+            total_staked: 0,
+            total_weight: 0,
+            reward_rate,
+            acc_reward_per_share: 0,
+            last_update: 0,
+            reward_end: 0,
+            remaining_rewards,
+            min_stake: 1,
+            stake_vault: Pubkey::default(),
+            rewards_vault: Pubkey::default(),
+            vault_authority_bump: 0,
+            pool_authority: Pubkey::default(),
+            pending_authority: None,
+            bump: 0,
+            lock_tiers: vec![ONE_X],
+            compound_tip_bps: 0,
+        };
+        pool.reset_reward_window(0);
+        pool
+    }
+
+    // Open a position the way stake does: update, checkpoint, add the amount, reweigh
+    fn stake(pool: &mut StakingPool, now: i64, amount: u64) -> UserStake {
+        let mut position = UserStake {
+            user: Pubkey::default(),
+            pool: Pubkey::default(),
+            position_id: 0,
+            amount: 0,
+            tier: 0,
+            weight: 0,
+            lock_until: 0,
+            rewards_earned: 0,
+            pending_rewards: 0,
+            reward_debt: 0,
+            bump: 0,
+            auto_compound: false,
+        };
+        pool.update(now);
+        position.checkpoint(pool);
+        position.amount += amount;
+        pool.total_staked += amount;
+        position.reweigh(pool).unwrap();
+        position
+    }
+
+    fn settle(pool: &mut StakingPool, position: &mut UserStake, now: i64) -> u64 {
+        pool.update(now);
+        position.checkpoint(pool);
+        position.pending_rewards
+    }
+
+    #[test]
+    fn emits_at_the_reward_rate_inside_the_window() {
+        let mut pool = pool(10, 1_000);
+        assert_eq!(pool.reward_end, 100);
+        let mut position = stake(&mut pool, 0, 100);
+
+        assert_eq!(settle(&mut pool, &mut position, 50), 500);
+        assert_eq!(pool.remaining_rewards, 500);
+    }
+
+    #[test]
+    fn stops_emitting_at_the_end_of_the_window() {
+        let mut pool = pool(10, 1_000);
+        let mut position = stake(&mut pool, 0, 100);
+
+        assert_eq!(settle(&mut pool, &mut position, 150), 1_000);
+        assert_eq!(pool.remaining_rewards, 0);
+        assert_eq!(settle(&mut pool, &mut position, 500), 1_000);
+    }
+
+    #[test]
+    fn idle_period_inside_the_window_slides_it() {
+        let mut pool = pool(10, 1_000);
+        pool.update(40);
+        assert_eq!(pool.reward_end, 140);
+        assert_eq!(pool.remaining_rewards, 1_000);
+        assert_eq!(pool.acc_reward_per_share, 0);
+
+        let mut position = stake(&mut pool, 40, 100);
+        assert_eq!(settle(&mut pool, &mut position, 140), 1_000);
+        assert_eq!(pool.remaining_rewards, 0);
+    }
+
+    #[test]
+    fn idle_period_past_the_window_keeps_the_budget() {
+        let mut pool = pool(10, 1_000);
+        pool.update(150);
+        assert_eq!(pool.reward_end, 250);
+
+        let mut position = stake(&mut pool, 150, 100);
+        assert_eq!(settle(&mut pool, &mut position, 200), 500);
+        assert_eq!(settle(&mut pool, &mut position, 400), 1_000);
+    }
+
+    #[test]
+    fn funding_mid_window_extends_it() {
+        let mut pool = pool(10, 1_000);
+        let mut position = stake(&mut pool, 0, 100);
+
+        // What fund does once the tokens arrived
+        pool.update(50);
+        pool.remaining_rewards += 500;
+        pool.reset_reward_window(50);
+        assert_eq!(pool.remaining_rewards, 1_000);
+        assert_eq!(pool.reward_end, 150);
+
+        assert_eq!(settle(&mut pool, &mut position, 150), 1_500);
+        assert_eq!(pool.remaining_rewards, 0);
+    }
+
+    #[test]
+    fn rounding_never_pays_out_more_than_was_emitted() {
+        let mut pool = pool(1, 10);
+        let mut positions: Vec<UserStake> = (0..3).map(|_| stake(&mut pool, 0, 1)).collect();
+
+        let paid: u64 = positions
+            .iter_mut()
+            .map(|position| settle(&mut pool, position, 10))
+            .sum();
+        assert_eq!(pool.acc_reward_per_share, 10 * ACC_REWARD_PRECISION / 3);
+        assert_eq!(paid, 9);
+        assert_eq!(pool.remaining_rewards, 1);
+    }
+
+    #[test]
+    fn rounded_away_rewards_stay_in_the_budget() {
+        // Weight so large that one second of emission rounds the increment down to zero
+        let mut pool = pool(1, 10);
+        let mut position = stake(&mut pool, 0, 2 * ACC_REWARD_PRECISION as u64);
+
+        assert_eq!(settle(&mut pool, &mut position, 1), 0);
+        assert_eq!(pool.acc_reward_per_share, 0);
+        assert_eq!(pool.remaining_rewards, 10);
+    }
+
+    #[test]
+    fn weight_applies_the_tier_multiplier_rounding_down() {
+        let boosted = LockTier {
+            lock_period: 0,
+            multiplier_bps: 15_000,
+        };
+        assert_eq!(ONE_X.weight(1_000).unwrap(), 1_000);
+        assert_eq!(boosted.weight(1_000).unwrap(), 1_500);
+        assert_eq!(boosted.weight(3).unwrap(), 4);
+    }
+
+    #[test]
+    fn weight_overflow_is_an_error() {
+        let max = LockTier {
+            lock_period: 0,
+            multiplier_bps: MAX_LOCK_MULTIPLIER_BPS,
+        };
+        assert!(max.weight(u64::MAX / 5).is_ok());
+        assert!(max.weight(u64::MAX / 5 + 1).is_err());
+    }
+
+    #[test]
+    fn lock_tiers_above_the_multiplier_cap_are_rejected() {
+        let capped = LockTier {
+            lock_period: 0,
+            multiplier_bps: MAX_LOCK_MULTIPLIER_BPS,
+        };
+        let over = LockTier {
+            lock_period: 0,
+            multiplier_bps: MAX_LOCK_MULTIPLIER_BPS + 1,
+        };
+        assert!(validate_lock_tiers(&[ONE_X, capped]).is_ok());
+        assert!(validate_lock_tiers(&[ONE_X, over]).is_err());
+    }
+
+    #[test]
+    fn reweigh_keeps_the_pool_total_in_sync() {
+        let mut pool = pool(10, 1_000);
+        pool.lock_tiers.push(LockTier {
+            lock_period: 0,
+            multiplier_bps: 20_000,
+        });
+        let _plain = stake(&mut pool, 0, 100);
+        let mut boosted = stake(&mut pool, 0, 0);
+        boosted.tier = 1;
+        boosted.amount = 100;
+        boosted.reweigh(&mut pool).unwrap();
+        assert_eq!(boosted.weight, 200);
+        assert_eq!(pool.total_weight, 300);
+
+        boosted.amount = 40;
+        boosted.reweigh(&mut pool).unwrap();
+        assert_eq!(boosted.weight, 80);
+        assert_eq!(pool.total_weight, 180);
+    }
+
+    #[test]
+    fn reweigh_overflowing_the_pool_total_is_an_error() {
+        let mut pool = pool(10, 1_000);
+        let mut position = stake(&mut pool, 0, 100);
+        pool.total_weight = u64::MAX;
+
+        position.amount = 200;
+        assert!(position.reweigh(&mut pool).is_err());
+        assert_eq!(position.weight, 100);
+        assert_eq!(pool.total_weight, u64::MAX);
+    }
+
+    #[test]
+    fn unstake_from_a_short_rewards_vault_keeps_the_shortfall_pending() {
+        let mut pool = pool(10, 1_000);
+        let mut position = stake(&mut pool, 0, 100);
+        assert_eq!(settle(&mut pool, &mut position, 50), 500);
+
+        // Vault only holds 200 of the 500 owed; the principal still comes out in full
+        assert_eq!(position.pay_rewards(200), 200);
+        position.withdraw(&mut pool, 100).unwrap();
+        assert_eq!(position.amount, 0);
+        assert_eq!(position.rewards_earned, 200);
+        assert_eq!(position.pending_rewards, 300);
+        assert_eq!(pool.total_staked, 0);
+        assert_eq!(pool.total_weight, 0);
+
+        // Nothing more accrues on the empty position, and the remainder is paid once funded
+        assert_eq!(settle(&mut pool, &mut position, 80), 300);
+        assert_eq!(position.pay_rewards(1_000), 300);
+        assert_eq!(position.pending_rewards, 0);
+        assert_eq!(position.rewards_earned, 500);
+    }
 }
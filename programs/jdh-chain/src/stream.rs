@@ -0,0 +1,341 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::config::{PauseError, ProgramConfig};
+use crate::transfer::transfer_in;
+
+// Payment Stream Account (deposit held in a PDA vault per stream)
+#[account]
+pub struct Stream {
+    pub id: u64,                         // Stream ID (per sender)
+    pub mint: Pubkey,                    // JDH Token mint
+    pub sender: Pubkey,                  // Funder, receives the unstreamed remainder
+    pub recipient: Pubkey,               // Receives streamed tokens
+    pub vault: Pubkey,                   // PDA token account holding the deposit
+    pub rate_per_second: u64,            // Tokens streamed per second
+    pub start_time: i64,                 // Streaming starts
+    pub end_time: i64,                   // Streaming ends
+    pub deposited: u64,                  // rate_per_second * (end_time - start_time)
+    pub withdrawn: u64,                  // Already withdrawn by the recipient
+    pub bump: u8,                        // Bump seed
+}
+
+impl Stream {
+    pub const LEN: usize = 8 + 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    // Amount streamed to the recipient up to a timestamp
+    pub fn accrued(&self, now: i64) -> u64 {
+        let elapsed = now.min(self.end_time) - self.start_time;
+        if elapsed <= 0 {
+            return 0;
+        }
+        self.rate_per_second * elapsed as u64
+    }
+}
+
+#[event]
+pub struct StreamCreated {
+    pub stream: Pubkey,
+    pub id: u64,
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub rate_per_second: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub deposited: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StreamWithdrawn {
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub withdrawn: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StreamCanceled {
+    pub stream: Pubkey,
+    pub canceled_by: Pubkey,
+    pub recipient_amount: u64,
+    pub sender_amount: u64,
+    pub timestamp: i64,
+}
+
+// Create Stream and deposit the full amount
+pub(crate) fn create(
+    ctx: Context<CreateStream>,
+    id: u64,
+    rate_per_second: u64,
+    start_time: i64,
+    end_time: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(rate_per_second > 0, StreamError::InvalidRate);
+    require!(
+        start_time >= clock.unix_timestamp && end_time > start_time,
+        StreamError::InvalidTimeRange
+    );
+    let deposited = rate_per_second
+        .checked_mul((end_time - start_time) as u64)
+        .ok_or(StreamError::AmountOverflow)?;
+
+    let received = transfer_in(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.sender_token_account.to_account_info(),
+        &mut ctx.accounts.vault,
+        ctx.accounts.sender.to_account_info(),
+        &[],
+        deposited,
+    )?;
+
+    // The rate is exact, so a withheld transfer fee would leave the stream underfunded
+    require!(received == deposited, StreamError::DepositShortfall);
+
+    let stream = &mut ctx.accounts.stream;
+    stream.id = id;
+    stream.mint = ctx.accounts.mint.key();
+    stream.sender = ctx.accounts.sender.key();
+    stream.recipient = ctx.accounts.recipient.key();
+    stream.vault = ctx.accounts.vault.key();
+    stream.rate_per_second = rate_per_second;
+    stream.start_time = start_time;
+    stream.end_time = end_time;
+    stream.deposited = deposited;
+    stream.withdrawn = 0;
+    stream.bump = ctx.bumps.stream;
+
+    emit!(StreamCreated {
+        stream: stream.key(),
+        id,
+        sender: stream.sender,
+        recipient: stream.recipient,
+        rate_per_second,
+        start_time,
+        end_time,
+        deposited,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Withdraw everything accrued so far (recipient)
+pub(crate) fn withdraw(ctx: Context<WithdrawFromStream>) -> Result<()> {
+    let clock = Clock::get()?;
+    let stream = &ctx.accounts.stream;
+
+    let available = stream.accrued(clock.unix_timestamp) - stream.withdrawn;
+    require!(available > 0, StreamError::NothingToWithdraw);
+
+    let id_bytes = stream.id.to_le_bytes();
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.recipient_token_account.to_account_info(),
+                authority: stream.to_account_info(),
+            },
+            &[&[
+                b"stream",
+                stream.sender.as_ref(),
+                id_bytes.as_ref(),
+                &[stream.bump],
+            ]],
+        ),
+        available,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let stream = &mut ctx.accounts.stream;
+    stream.withdrawn += available;
+
+    emit!(StreamWithdrawn {
+        stream: stream.key(),
+        recipient: stream.recipient,
+        amount: available,
+        withdrawn: stream.withdrawn,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Cancel Stream (sender or recipient): accrued goes to the recipient, the rest to the sender
+pub(crate) fn cancel(ctx: Context<CancelStream>) -> Result<()> {
+    let clock = Clock::get()?;
+    let stream = &ctx.accounts.stream;
+    let signer = ctx.accounts.signer.key();
+
+    require!(
+        signer == stream.sender || signer == stream.recipient,
+        StreamError::Unauthorized
+    );
+
+    let accrued = stream.accrued(clock.unix_timestamp);
+    let recipient_amount = accrued - stream.withdrawn;
+    let sender_amount = stream.deposited - accrued;
+
+    let id_bytes = stream.id.to_le_bytes();
+    let seeds: &[&[&[u8]]] = &[&[
+        b"stream",
+        stream.sender.as_ref(),
+        id_bytes.as_ref(),
+        &[stream.bump],
+    ]];
+
+    for (destination, amount) in [
+        (ctx.accounts.recipient_token_account.to_account_info(), recipient_amount),
+        (ctx.accounts.sender_token_account.to_account_info(), sender_amount),
+    ] {
+        if amount > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.vault.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: destination,
+                        authority: stream.to_account_info(),
+                    },
+                    seeds,
+                ),
+                amount,
+                ctx.accounts.mint.decimals,
+            )?;
+        }
+    }
+
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.vault.to_account_info(),
+            destination: ctx.accounts.sender.to_account_info(),
+            authority: stream.to_account_info(),
+        },
+        seeds,
+    ))?;
+
+    emit!(StreamCanceled {
+        stream: stream.key(),
+        canceled_by: signer,
+        recipient_amount,
+        sender_amount,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateStream<'info> {
+    #[account(
+        init,
+        payer = sender,
+        space = Stream::LEN,
+        seeds = [b"stream", sender.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub stream: Account<'info, Stream>,
+    #[account(
+        init,
+        payer = sender,
+        seeds = [b"stream-vault", stream.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = stream,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: Only recorded as the recipient
+    pub recipient: UncheckedAccount<'info>,
+    #[account(mut, token::mint = mint, token::authority = sender)]
+    pub sender_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFromStream<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        has_one = recipient,
+        has_one = vault
+    )]
+    pub stream: Account<'info, Stream>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub recipient: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CancelStream<'info> {
+    #[account(
+        mut,
+        close = sender,
+        has_one = mint,
+        has_one = sender,
+        has_one = vault
+    )]
+    pub stream: Account<'info, Stream>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = stream.sender)]
+    pub sender_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint, token::authority = stream.recipient)]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Stream sender, receives the rent
+    #[account(mut)]
+    pub sender: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[error_code]
+pub enum StreamError {
+    #[msg("Rate must be greater than zero")]
+    InvalidRate,
+    #[msg("Invalid stream time range")]
+    InvalidTimeRange,
+    #[msg("Amount overflow")]
+    AmountOverflow,
+    #[msg("Vault received less than the stream deposit")]
+    DepositShortfall,
+    #[msg("Nothing to withdraw")]
+    NothingToWithdraw,
+    #[msg("Signer is not allowed to cancel this stream")]
+    Unauthorized,
+}
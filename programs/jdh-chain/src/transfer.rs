@@ -0,0 +1,239 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions},
+    state::Account as TokenAccountState,
+};
+use anchor_spl::token_interface::{
+    self, HarvestWithheldTokensToMint, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::config::{PauseError, ProgramConfig};
+use crate::fee::{self, FeeError};
+
+pub const MAX_BATCH_RECIPIENTS: usize = 20;
+
+#[event]
+pub struct TokensTransferred {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,                     // Net amount received by `to`
+    pub fee: u64,                        // Protocol fee routed to the treasury
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BatchTransferred {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub authority: Pubkey,
+    pub recipients: u8,
+    pub total_amount: u64,               // Net amount received by all recipients
+    pub total_fee: u64,                  // Protocol fees routed to the treasury
+    pub timestamp: i64,
+}
+
+// Transfer into a program-held token account and return the amount that arrived.
+// Token-2022 transfer fees can withhold part of the amount, so callers credit the
+// balance delta instead of what was sent. Pass no signer seeds for a wallet signer.
+pub(crate) fn transfer_in<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    from: AccountInfo<'info>,
+    to: &mut InterfaceAccount<'info, TokenAccount>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<u64> {
+    let balance_before = to.amount;
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from,
+                mint: mint.to_account_info(),
+                to: to.to_account_info(),
+                authority,
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )?;
+    to.reload()?;
+    Ok(to.amount - balance_before)
+}
+
+// Move Token-2022 transfer fees withheld in a program-held token account to the mint, so the
+// account can be closed. Classic SPL Token accounts and fee-free mints have nothing withheld.
+pub(crate) fn harvest_withheld<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+) -> Result<()> {
+    if withheld_amount(&account)? == 0 {
+        return Ok(());
+    }
+    token_interface::harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            token_program.clone(),
+            HarvestWithheldTokensToMint {
+                token_program_id: token_program,
+                mint,
+            },
+        ),
+        vec![account],
+    )
+}
+
+fn withheld_amount(account: &AccountInfo) -> Result<u64> {
+    if account.owner != &anchor_spl::token_2022::ID {
+        return Ok(0);
+    }
+    let data = account.try_borrow_data()?;
+    let state = StateWithExtensions::<TokenAccountState>::unpack(&data)?;
+    Ok(state
+        .get_extension::<TransferFeeAmount>()
+        .map_or(0, |fee| u64::from(fee.withheld_amount)))
+}
+
+// Batch Transfer: amounts[i] goes to remaining_accounts[i], all or nothing
+// Each recipient is charged the transfer fee as if sent on its own, paid to the treasury once
+pub fn batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchTransfer<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    let recipients = ctx.remaining_accounts;
+
+    require!(!amounts.is_empty(), TransferError::EmptyBatch);
+    require!(
+        amounts.len() <= MAX_BATCH_RECIPIENTS,
+        TransferError::BatchTooLarge
+    );
+    require!(
+        amounts.len() == recipients.len(),
+        TransferError::BatchLengthMismatch
+    );
+
+    let mut fee_config =
+        fee::load_config(&ctx.accounts.fee_config, ctx.accounts.treasury.as_ref())?;
+    let mut total_amount: u64 = 0;
+    let mut total_fee: u64 = 0;
+    for (recipient, amount) in recipients.iter().zip(amounts.iter()) {
+        // Recipient must be a token account of this token program and mint
+        require_keys_eq!(
+            *recipient.owner,
+            ctx.accounts.token_program.key(),
+            TransferError::InvalidRecipientOwner
+        );
+        let recipient_account = InterfaceAccount::<TokenAccount>::try_from(recipient)?;
+        require_keys_eq!(
+            recipient_account.mint,
+            ctx.accounts.mint.key(),
+            TransferError::InvalidRecipientMint
+        );
+
+        let fee = fee::fee_owed(
+            fee_config.as_ref(),
+            *amount,
+            &ctx.accounts.from.owner,
+            &recipient_account.owner,
+        );
+        let net_amount = amount - fee;
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.from.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: recipient.clone(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            net_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        total_amount = total_amount
+            .checked_add(net_amount)
+            .ok_or(TransferError::AmountOverflow)?;
+        total_fee = total_fee
+            .checked_add(fee)
+            .ok_or(TransferError::AmountOverflow)?;
+    }
+
+    if total_fee > 0 {
+        let treasury = ctx
+            .accounts
+            .treasury
+            .as_ref()
+            .ok_or(FeeError::MissingTreasury)?;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.from.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: treasury.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            total_fee,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        if let Some(fee_config) = fee_config.as_mut() {
+            fee::record_collected(&ctx.accounts.fee_config, fee_config, total_fee)?;
+        }
+    }
+
+    emit!(BatchTransferred {
+        mint: ctx.accounts.mint.key(),
+        from: ctx.accounts.from.key(),
+        authority: ctx.accounts.authority.key(),
+        recipients: amounts.len() as u8,
+        total_amount,
+        total_fee,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BatchTransfer<'info> {
+    /// CHECK: The mint's fee-config PDA, read by fee::load_config once it has been initialized
+    #[account(mut, seeds = [b"fee-config", mint.key().as_ref()], bump)]
+    pub fee_config: UncheckedAccount<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    // Required once the mint has a fee config
+    #[account(mut, token::mint = mint)]
+    pub treasury: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[error_code]
+pub enum TransferError {
+    #[msg("Batch has no recipients")]
+    EmptyBatch,
+    #[msg("Batch has too many recipients")]
+    BatchTooLarge,
+    #[msg("Amounts and recipient accounts differ in length")]
+    BatchLengthMismatch,
+    #[msg("Recipient is not owned by the token program")]
+    InvalidRecipientOwner,
+    #[msg("Recipient token account has the wrong mint")]
+    InvalidRecipientMint,
+    #[msg("Amount overflow")]
+    AmountOverflow,
+}
@@ -0,0 +1,345 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::config::{PauseError, ProgramConfig};
+use crate::transfer::transfer_in;
+
+// Vesting Schedule Account (tokens held in a PDA vault per schedule)
+#[account]
+pub struct VestingSchedule {
+    pub id: u64,                         // Schedule ID (per beneficiary)
+    pub mint: Pubkey,                    // JDH Token mint
+    pub creator: Pubkey,                 // Funder, may revoke if revocable
+    pub beneficiary: Pubkey,             // Receives vested tokens
+    pub vault: Pubkey,                   // PDA token account holding the grant
+    pub treasury: Pubkey,                // Receives unvested tokens on revoke
+    pub total_amount: u64,               // Total granted amount
+    pub claimed_amount: u64,             // Amount already claimed
+    pub start_ts: i64,                   // Vesting start
+    pub cliff_ts: i64,                   // Nothing vests before this
+    pub duration: i64,                   // Linear vesting duration in seconds
+    pub period: i64,                     // Step period in seconds (0 = continuous)
+    pub revocable: bool,                 // Whether creator can revoke
+    pub revoked_at: i64,                 // Revocation timestamp (0 = not revoked)
+    pub bump: u8,                        // Bump seed
+}
+
+impl VestingSchedule {
+    pub const LEN: usize = 8 + 8 + 32 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1;
+
+    // Amount vested at a timestamp, frozen at the revocation time
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        let now = if self.revoked_at > 0 { now.min(self.revoked_at) } else { now };
+
+        if now < self.cliff_ts {
+            return 0;
+        }
+
+        let mut elapsed = now - self.start_ts;
+        if elapsed >= self.duration {
+            return self.total_amount;
+        }
+        if self.period > 0 {
+            elapsed -= elapsed % self.period;
+        }
+
+        (self.total_amount as u128 * elapsed as u128 / self.duration as u128) as u64
+    }
+}
+
+#[event]
+pub struct VestingCreated {
+    pub schedule: Pubkey,
+    pub id: u64,
+    pub creator: Pubkey,
+    pub beneficiary: Pubkey,
+    pub total_amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub duration: i64,
+    pub period: i64,
+    pub revocable: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub schedule: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub claimed_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub schedule: Pubkey,
+    pub creator: Pubkey,
+    pub treasury: Pubkey,
+    pub unvested_returned: u64,
+    pub timestamp: i64,
+}
+
+// Create Vesting Schedule
+#[allow(clippy::too_many_arguments)]
+pub fn create_schedule(
+    ctx: Context<CreateVesting>,
+    id: u64,
+    total_amount: u64,
+    start_ts: i64,
+    cliff_ts: i64,
+    duration: i64,
+    period: Option<i64>,
+    revocable: bool,
+) -> Result<()> {
+    let period = period.unwrap_or(0);
+
+    require!(total_amount > 0, VestingError::InvalidAmount);
+    require!(duration > 0, VestingError::InvalidSchedule);
+    require!(
+        cliff_ts >= start_ts && cliff_ts <= start_ts + duration,
+        VestingError::InvalidSchedule
+    );
+    require!(
+        (0..=duration).contains(&period),
+        VestingError::InvalidSchedule
+    );
+
+    // Fund the vault
+    let received = transfer_in(
+        &ctx.accounts.token_program,
+        &ctx.accounts.mint,
+        ctx.accounts.creator_token_account.to_account_info(),
+        &mut ctx.accounts.vault,
+        ctx.accounts.creator.to_account_info(),
+        &[],
+        total_amount,
+    )?;
+
+    let schedule = &mut ctx.accounts.schedule;
+    schedule.id = id;
+    schedule.mint = ctx.accounts.mint.key();
+    schedule.creator = ctx.accounts.creator.key();
+    schedule.beneficiary = ctx.accounts.beneficiary.key();
+    schedule.vault = ctx.accounts.vault.key();
+    schedule.treasury = ctx.accounts.treasury.key();
+    schedule.total_amount = received;
+    schedule.claimed_amount = 0;
+    schedule.start_ts = start_ts;
+    schedule.cliff_ts = cliff_ts;
+    schedule.duration = duration;
+    schedule.period = period;
+    schedule.revocable = revocable;
+    schedule.revoked_at = 0;
+    schedule.bump = ctx.bumps.schedule;
+
+    emit!(VestingCreated {
+        schedule: schedule.key(),
+        id,
+        creator: schedule.creator,
+        beneficiary: schedule.beneficiary,
+        total_amount: schedule.total_amount,
+        start_ts,
+        cliff_ts,
+        duration,
+        period,
+        revocable,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Claim Vested Tokens
+pub fn claim(ctx: Context<ClaimVested>) -> Result<()> {
+    let clock = Clock::get()?;
+    let schedule = &ctx.accounts.schedule;
+
+    let claimable = schedule
+        .vested_amount(clock.unix_timestamp)
+        .saturating_sub(schedule.claimed_amount);
+    require!(claimable > 0, VestingError::NothingToClaim);
+
+    let id_bytes = schedule.id.to_le_bytes();
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.beneficiary_token_account.to_account_info(),
+                authority: schedule.to_account_info(),
+            },
+            &[&[
+                b"vesting",
+                schedule.mint.as_ref(),
+                schedule.beneficiary.as_ref(),
+                id_bytes.as_ref(),
+                &[schedule.bump],
+            ]],
+        ),
+        claimable,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let schedule = &mut ctx.accounts.schedule;
+    schedule.claimed_amount += claimable;
+
+    emit!(VestedClaimed {
+        schedule: schedule.key(),
+        beneficiary: schedule.beneficiary,
+        amount: claimable,
+        claimed_amount: schedule.claimed_amount,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+// Revoke Vesting: vested tokens stay claimable, unvested go to the treasury
+pub fn revoke(ctx: Context<RevokeVesting>) -> Result<()> {
+    let clock = Clock::get()?;
+    let schedule = &ctx.accounts.schedule;
+
+    require!(schedule.revocable, VestingError::NotRevocable);
+    require!(schedule.revoked_at == 0, VestingError::AlreadyRevoked);
+
+    let unvested = schedule.total_amount - schedule.vested_amount(clock.unix_timestamp);
+
+    if unvested > 0 {
+        let id_bytes = schedule.id.to_le_bytes();
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                    authority: schedule.to_account_info(),
+                },
+                &[&[
+                    b"vesting",
+                    schedule.mint.as_ref(),
+                    schedule.beneficiary.as_ref(),
+                    id_bytes.as_ref(),
+                    &[schedule.bump],
+                ]],
+            ),
+            unvested,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    let schedule = &mut ctx.accounts.schedule;
+    schedule.revoked_at = clock.unix_timestamp;
+
+    emit!(VestingRevoked {
+        schedule: schedule.key(),
+        creator: schedule.creator,
+        treasury: schedule.treasury,
+        unvested_returned: unvested,
+        timestamp: clock.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateVesting<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = VestingSchedule::LEN,
+        seeds = [b"vesting", mint.key().as_ref(), beneficiary.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"vesting-vault", schedule.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = schedule,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: Only recorded as the beneficiary
+    pub beneficiary: UncheckedAccount<'info>,
+    #[account(mut, token::mint = mint, token::authority = creator)]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(token::mint = mint)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        has_one = beneficiary,
+        has_one = vault
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub beneficiary: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        has_one = creator,
+        has_one = vault,
+        has_one = treasury
+    )]
+    pub schedule: Account<'info, VestingSchedule>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+    pub creator: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"program-config"],
+        bump = program_config.bump,
+        constraint = !program_config.token_ops_paused @ PauseError::TokenOpsPaused
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+#[error_code]
+pub enum VestingError {
+    #[msg("Vesting amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Invalid vesting schedule")]
+    InvalidSchedule,
+    #[msg("No vested tokens to claim")]
+    NothingToClaim,
+    #[msg("Vesting schedule is not revocable")]
+    NotRevocable,
+    #[msg("Vesting schedule already revoked")]
+    AlreadyRevoked,
+}
@@ -0,0 +1,1508 @@
+import * as anchor from '@coral-xyz/anchor';
+import { BN, Program } from '@coral-xyz/anchor';
+import { TOKEN_2022_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair, PublicKey, SystemProgram } from '@solana/web3.js';
+import { expect } from 'chai';
+import { createHash } from 'crypto';
+
+import { JdhChain } from '../../target/types/jdh_chain';
+import {
+  chainTime,
+  createJdhMint,
+  DECIMALS,
+  ensureProgramConfig,
+  eventsOf,
+  findEvent,
+  fundedKeypair,
+  initializeMiningVault,
+  initializeStakingPool,
+  JdhMint,
+  mintJdh,
+  pda,
+  provider,
+  tokenAccount,
+  u64Seed,
+  waitForChainTime,
+} from './helpers';
+
+function expectKey(actual: PublicKey, expected: PublicKey) {
+  expect(actual.toBase58()).to.equal(expected.toBase58());
+}
+
+function expectAmount(actual: BN, expected: number) {
+  expect(actual.toNumber()).to.equal(expected);
+}
+
+// Indexers read events from transaction logs, so every event must decode through the IDL
+// with the keys and running totals the handler saw
+describe('jdh-chain events', () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.JdhChain as Program<JdhChain>;
+  const connection = anchor.getProvider().connection;
+  const wallet = provider().wallet.publicKey;
+  const tokenProgram = TOKEN_2022_PROGRAM_ID;
+  const MINTED = 100_000_000;
+
+  let programConfig: PublicKey;
+  let jdh: JdhMint;
+  let user: Keypair;
+  let userTokenAccount: PublicKey;
+  let mintSignature: string;
+  let nextId = Date.now();
+
+  before(async () => {
+    programConfig = await ensureProgramConfig(program);
+    jdh = await createJdhMint(program, tokenProgram);
+    user = await fundedKeypair(connection);
+    const minted = await mintJdh(program, jdh, user.publicKey, MINTED);
+    userTokenAccount = minted.account;
+    mintSignature = minted.signature;
+  });
+
+  describe('config', () => {
+    it('ProgramConfigInitialized', async () => {
+      // The singleton is initialized once per validator, by whichever suite ran first
+      let oldest: string | undefined;
+      for (;;) {
+        const page = await connection.getSignaturesForAddress(
+          programConfig,
+          { before: oldest, limit: 1_000 },
+          'confirmed',
+        );
+        if (page.length === 0) break;
+        oldest = page[page.length - 1].signature;
+      }
+
+      const event = findEvent(await eventsOf(program, oldest!), 'ProgramConfigInitialized');
+      expectKey(event.admin, wallet);
+      expectKey(event.guardian, wallet);
+    });
+
+    it('SubsystemPaused and SubsystemUnpaused', async () => {
+      const paused = await program.methods
+        .pause({ governance: {} })
+        .accountsPartial({ programConfig, signer: wallet })
+        .rpc({ commitment: 'confirmed' });
+      const unpaused = await program.methods
+        .unpause({ governance: {} })
+        .accountsPartial({ programConfig, signer: wallet })
+        .rpc({ commitment: 'confirmed' });
+
+      const pausedEvent = findEvent(await eventsOf(program, paused), 'SubsystemPaused');
+      expect(pausedEvent.subsystem).to.deep.equal({ governance: {} });
+      expectKey(pausedEvent.by, wallet);
+
+      const unpausedEvent = findEvent(await eventsOf(program, unpaused), 'SubsystemUnpaused');
+      expect(unpausedEvent.subsystem).to.deep.equal({ governance: {} });
+      expectKey(unpausedEvent.by, wallet);
+    });
+
+    it('GuardianUpdated', async () => {
+      const { guardian } = await program.account.programConfig.fetch(programConfig);
+      const newGuardian = Keypair.generate().publicKey;
+
+      const signature = await program.methods
+        .setGuardian(newGuardian)
+        .accountsPartial({ programConfig, admin: wallet })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'GuardianUpdated');
+      expectKey(event.oldGuardian, guardian);
+      expectKey(event.newGuardian, newGuardian);
+
+      await program.methods
+        .setGuardian(guardian)
+        .accountsPartial({ programConfig, admin: wallet })
+        .rpc({ commitment: 'confirmed' });
+    });
+  });
+
+  describe('token', () => {
+    it('MintInitialized', async () => {
+      const transferFee = { transferFeeBps: 50, maximumFee: new BN(5_000) };
+      const feeMint = await createJdhMint(program, tokenProgram, transferFee);
+
+      const event = findEvent(await eventsOf(program, feeMint.mintSignature), 'MintInitialized');
+      expectKey(event.mint, feeMint.mint);
+      expectKey(event.authority, wallet);
+      expectKey(event.tokenProgram, tokenProgram);
+      expect(event.decimals).to.equal(DECIMALS);
+      expectAmount(event.maxSupply, 1_000_000_000_000_000);
+      expectAmount(event.epochDuration, 86_400);
+      expectAmount(event.initialEpochEmission, 100_000_000_000_000);
+      expect(event.emissionDecayBps).to.equal(0);
+      expect(event.transferFee.transferFeeBps).to.equal(50);
+      expectAmount(event.transferFee.maximumFee, 5_000);
+    });
+
+    it('TokensMinted', async () => {
+      const event = findEvent(await eventsOf(program, mintSignature), 'TokensMinted');
+      expectKey(event.mint, jdh.mint);
+      expectKey(event.minter, wallet);
+      expectKey(event.to, userTokenAccount);
+      expectAmount(event.amount, MINTED);
+      expectAmount(event.minterTotalMinted, MINTED);
+      expectAmount(event.totalMinted, MINTED);
+    });
+
+    it('TokensBurned', async () => {
+      const signature = await program.methods
+        .burnTokens(new BN(500_000), { user: {} })
+        .accountsPartial({
+          config: jdh.config,
+          mint: jdh.mint,
+          from: userTokenAccount,
+          burnLedger: pda(program, ['burn-ledger', jdh.mint]),
+          userBurn: pda(program, ['user-burn', jdh.mint, user.publicKey]),
+          authority: user.publicKey,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'TokensBurned');
+      expectKey(event.mint, jdh.mint);
+      expectKey(event.user, user.publicKey);
+      expectKey(event.from, userTokenAccount);
+      expectAmount(event.amount, 500_000);
+      expectAmount(event.userTotalBurned, 500_000);
+      expectAmount(event.totalBurned, 500_000);
+    });
+  });
+
+  describe('minter', () => {
+    it('MinterAdded', async () => {
+      const event = findEvent(await eventsOf(program, jdh.minterSignature), 'MinterAdded');
+      expectKey(event.config, jdh.config);
+      expectKey(event.minter, wallet);
+      expectAmount(event.totalAllowance, 100_000_000_000_000);
+      expectAmount(event.windowAllowance, 100_000_000_000_000);
+      expectAmount(event.windowDuration, 86_400);
+    });
+
+    it('MinterRemoved', async () => {
+      const minter = Keypair.generate().publicKey;
+      const minterAllowance = pda(program, ['minter', jdh.config, minter]);
+      await program.methods
+        .addMinter(new BN(1_000), new BN(1_000), new BN(60))
+        .accountsPartial({
+          config: jdh.config,
+          minterAllowance,
+          minter,
+          authority: wallet,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+      const signature = await program.methods
+        .removeMinter()
+        .accountsPartial({ config: jdh.config, minterAllowance, authority: wallet })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'MinterRemoved');
+      expectKey(event.config, jdh.config);
+      expectKey(event.minter, minter);
+      expectAmount(event.totalMinted, 0);
+    });
+  });
+
+  describe('metadata', () => {
+    it('MetadataInitialized', async () => {
+      const signature = await program.methods
+        .initializeMetadata('JDH', 'JDH', 'https://example.com/jdh.json')
+        .accountsPartial({
+          config: jdh.config,
+          mint: jdh.mint,
+          mintAuthority: pda(program, ['mint-authority', jdh.mint]),
+          authority: wallet,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'MetadataInitialized');
+      expectKey(event.mint, jdh.mint);
+      expect(event.name).to.equal('JDH');
+      expect(event.symbol).to.equal('JDH');
+      expect(event.uri).to.equal('https://example.com/jdh.json');
+    });
+
+    it('MetadataFieldUpdated', async () => {
+      const signature = await program.methods
+        .updateMetadataField({ uri: {} }, 'https://example.com/jdh-v2.json')
+        .accountsPartial({
+          config: jdh.config,
+          mint: jdh.mint,
+          mintAuthority: pda(program, ['mint-authority', jdh.mint]),
+          authority: wallet,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'MetadataFieldUpdated');
+      expectKey(event.mint, jdh.mint);
+      expect(event.field).to.deep.equal({ uri: {} });
+      expect(event.value).to.equal('https://example.com/jdh-v2.json');
+    });
+  });
+
+  describe('fee and transfer', () => {
+    let feeConfig: PublicKey;
+    let treasury: PublicKey;
+
+    it('fee: FeeConfigUpdated on initialize', async () => {
+      feeConfig = pda(program, ['fee-config', jdh.mint]);
+      treasury = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+
+      const signature = await program.methods
+        .initializeFeeConfig(100, new BN(1_000_000))
+        .accountsPartial({
+          config: jdh.config,
+          feeConfig,
+          mint: jdh.mint,
+          treasury,
+          authority: wallet,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'FeeConfigUpdated');
+      expectKey(event.mint, jdh.mint);
+      expectKey(event.treasury, treasury);
+      expect(event.transferFeeBps).to.equal(100);
+      expectAmount(event.maxFee, 1_000_000);
+    });
+
+    it('transfer: TokensTransferred', async () => {
+      const to = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+
+      const signature = await program.methods
+        .transferTokens(new BN(1_000_000))
+        .accountsPartial({
+          feeConfig,
+          mint: jdh.mint,
+          from: userTokenAccount,
+          to,
+          treasury,
+          authority: user.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'TokensTransferred');
+      expectKey(event.mint, jdh.mint);
+      expectKey(event.from, userTokenAccount);
+      expectKey(event.to, to);
+      expectKey(event.authority, user.publicKey);
+      expectAmount(event.amount, 990_000);
+      expectAmount(event.fee, 10_000);
+    });
+
+    it('transfer: BatchTransferred', async () => {
+      const recipients = [
+        await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram),
+        await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram),
+      ];
+
+      const signature = await program.methods
+        .batchTransfer([new BN(1_000_000), new BN(1_000_000)])
+        .accountsPartial({
+          feeConfig,
+          mint: jdh.mint,
+          from: userTokenAccount,
+          treasury,
+          authority: user.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .remainingAccounts(recipients.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'BatchTransferred');
+      expectKey(event.mint, jdh.mint);
+      expectKey(event.from, userTokenAccount);
+      expectKey(event.authority, user.publicKey);
+      expect(event.recipients).to.equal(2);
+      expectAmount(event.totalAmount, 1_980_000);
+      expectAmount(event.totalFee, 20_000);
+    });
+
+    it('fee: FeeConfigUpdated on update', async () => {
+      const newTreasury = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+
+      const signature = await program.methods
+        .updateFeeConfig(200, new BN(500_000))
+        .accountsPartial({ config: jdh.config, feeConfig, treasury: newTreasury, authority: wallet })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'FeeConfigUpdated');
+      expectKey(event.mint, jdh.mint);
+      expectKey(event.treasury, newTreasury);
+      expect(event.transferFeeBps).to.equal(200);
+      expectAmount(event.maxFee, 500_000);
+
+      // Later suites transfer on this mint, restore the original config
+      await program.methods
+        .updateFeeConfig(100, new BN(1_000_000))
+        .accountsPartial({ config: jdh.config, feeConfig, treasury, authority: wallet })
+        .rpc({ commitment: 'confirmed' });
+    });
+
+    it('fee: FeeExemptionSet', async () => {
+      const owner = Keypair.generate().publicKey;
+
+      const signature = await program.methods
+        .setFeeExemption(owner, true)
+        .accountsPartial({ config: jdh.config, feeConfig, authority: wallet })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'FeeExemptionSet');
+      expectKey(event.mint, jdh.mint);
+      expectKey(event.owner, owner);
+      expect(event.exempt).to.equal(true);
+    });
+  });
+
+  describe('compliance', () => {
+    it('AccountFrozen and AccountThawed, one record per freeze', async () => {
+      const owner = Keypair.generate().publicKey;
+      const frozen = await tokenAccount(jdh.mint, owner, tokenProgram);
+      const freezeCounter = pda(program, ['freeze-counter', frozen]);
+      const record = (index: number) => pda(program, ['freeze-record', frozen, u64Seed(index)]);
+
+      const freeze = (reasonCode: number, index: number) =>
+        program.methods
+          .freezeAccount(reasonCode)
+          .accountsPartial({
+            config: jdh.config,
+            mint: jdh.mint,
+            tokenAccount: frozen,
+            freezeCounter,
+            freezeRecord: record(index),
+            freezeAuthority: pda(program, ['freeze-authority', jdh.mint]),
+            complianceAuthority: wallet,
+            tokenProgram,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc({ commitment: 'confirmed' });
+      const thaw = (reasonCode: number, index: number) =>
+        program.methods
+          .thawAccount(reasonCode)
+          .accountsPartial({
+            config: jdh.config,
+            mint: jdh.mint,
+            tokenAccount: frozen,
+            freezeRecord: record(index),
+            freezeAuthority: pda(program, ['freeze-authority', jdh.mint]),
+            complianceAuthority: wallet,
+            tokenProgram,
+          })
+          .rpc({ commitment: 'confirmed' });
+
+      const frozenEvent = findEvent(await eventsOf(program, await freeze(1, 0)), 'AccountFrozen');
+      expectKey(frozenEvent.tokenAccount, frozen);
+      expectKey(frozenEvent.owner, owner);
+      expectKey(frozenEvent.mint, jdh.mint);
+      expectKey(frozenEvent.actor, wallet);
+      expectKey(frozenEvent.record, record(0));
+      expect(frozenEvent.reasonCode).to.equal(1);
+      expectAmount(frozenEvent.freezeCount, 1);
+
+      const thawedEvent = findEvent(await eventsOf(program, await thaw(2, 0)), 'AccountThawed');
+      expectKey(thawedEvent.tokenAccount, frozen);
+      expectKey(thawedEvent.owner, owner);
+      expectKey(thawedEvent.mint, jdh.mint);
+      expectKey(thawedEvent.actor, wallet);
+      expectKey(thawedEvent.record, record(0));
+      expect(thawedEvent.reasonCode).to.equal(2);
+
+      const refrozenEvent = findEvent(await eventsOf(program, await freeze(3, 1)), 'AccountFrozen');
+      expectKey(refrozenEvent.record, record(1));
+      expectAmount(refrozenEvent.freezeCount, 2);
+
+      // The second freeze leaves the first freeze and its thaw on record
+      const first = await program.account.freezeRecord.fetch(record(0));
+      expectAmount(first.index, 0);
+      expect(first.freezeReason).to.equal(1);
+      expect(first.thawReason).to.equal(2);
+      expectKey(first.frozenBy, wallet);
+      expectKey(first.thawedBy, wallet);
+      expect(first.thawedAt.toNumber()).to.be.at.least(first.frozenAt.toNumber());
+
+      const second = await program.account.freezeRecord.fetch(record(1));
+      expectAmount(second.index, 1);
+      expect(second.freezeReason).to.equal(3);
+      expect(second.thawReason).to.equal(0);
+      expectAmount(second.thawedAt, 0);
+      expectAmount((await program.account.freezeCounter.fetch(freezeCounter)).freezeCount, 2);
+    });
+
+    it('ComplianceAuthorityUpdated', async () => {
+      const newAuthority = Keypair.generate().publicKey;
+
+      const signature = await program.methods
+        .setComplianceAuthority(newAuthority)
+        .accountsPartial({ config: jdh.config, authority: wallet })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'ComplianceAuthorityUpdated');
+      expectKey(event.mint, jdh.mint);
+      expectKey(event.oldAuthority, wallet);
+      expectKey(event.newAuthority, newAuthority);
+
+      await program.methods
+        .setComplianceAuthority(wallet)
+        .accountsPartial({ config: jdh.config, authority: wallet })
+        .rpc({ commitment: 'confirmed' });
+    });
+  });
+
+  describe('authority', () => {
+    it('AuthorityProposed', async () => {
+      const pendingAuthority = Keypair.generate().publicKey;
+
+      const signature = await program.methods
+        .proposeTokenAuthority(pendingAuthority)
+        .accountsPartial({ config: jdh.config, authority: wallet })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'AuthorityProposed');
+      expectKey(event.account, jdh.config);
+      expectKey(event.authority, wallet);
+      expectKey(event.pendingAuthority, pendingAuthority);
+
+      // Cancel the proposal so the wallet stays the token authority
+      await program.methods
+        .proposeTokenAuthority(null)
+        .accountsPartial({ config: jdh.config, authority: wallet })
+        .rpc({ commitment: 'confirmed' });
+    });
+
+    it('AuthorityAccepted and AuthorityRenounced', async () => {
+      // A throwaway mint, renouncing is permanent
+      const other = await createJdhMint(program, tokenProgram);
+      const newAuthority = Keypair.generate();
+      await program.methods
+        .proposeTokenAuthority(newAuthority.publicKey)
+        .accountsPartial({ config: other.config, authority: wallet })
+        .rpc({ commitment: 'confirmed' });
+
+      const accepted = await program.methods
+        .acceptTokenAuthority()
+        .accountsPartial({ config: other.config, newAuthority: newAuthority.publicKey })
+        .signers([newAuthority])
+        .rpc({ commitment: 'confirmed' });
+      const renounced = await program.methods
+        .renounceTokenAuthority()
+        .accountsPartial({ config: other.config, authority: newAuthority.publicKey })
+        .signers([newAuthority])
+        .rpc({ commitment: 'confirmed' });
+
+      const acceptedEvent = findEvent(await eventsOf(program, accepted), 'AuthorityAccepted');
+      expectKey(acceptedEvent.account, other.config);
+      expectKey(acceptedEvent.previousAuthority, wallet);
+      expectKey(acceptedEvent.authority, newAuthority.publicKey);
+
+      const renouncedEvent = findEvent(await eventsOf(program, renounced), 'AuthorityRenounced');
+      expectKey(renouncedEvent.account, other.config);
+      expectKey(renouncedEvent.previousAuthority, newAuthority.publicKey);
+    });
+  });
+
+  describe('allowance', () => {
+    const spender = Keypair.generate();
+    let allowance: PublicKey;
+
+    it('AllowanceGranted', async () => {
+      allowance = pda(program, ['allowance', userTokenAccount, spender.publicKey]);
+      const expiresAt = (await chainTime(connection)) + 86_400;
+
+      const signature = await program.methods
+        .grantAllowance(new BN(1_000_000), new BN(3_600), new BN(expiresAt))
+        .accountsPartial({
+          allowance,
+          counter: pda(program, ['allowance-counter', userTokenAccount]),
+          mint: jdh.mint,
+          tokenAccount: userTokenAccount,
+          delegate: pda(program, ['allowance-delegate', userTokenAccount]),
+          spender: spender.publicKey,
+          owner: user.publicKey,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'AllowanceGranted');
+      expectKey(event.allowance, allowance);
+      expectKey(event.owner, user.publicKey);
+      expectKey(event.tokenAccount, userTokenAccount);
+      expectKey(event.spender, spender.publicKey);
+      expectAmount(event.limitPerPeriod, 1_000_000);
+      expectAmount(event.periodDuration, 3_600);
+      expectAmount(event.expiresAt, expiresAt);
+    });
+
+    it('AllowanceSpent', async () => {
+      const destination = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+
+      const signature = await program.methods
+        .spendAllowance(new BN(400_000))
+        .accountsPartial({
+          allowance,
+          mint: jdh.mint,
+          tokenAccount: userTokenAccount,
+          destination,
+          delegate: pda(program, ['allowance-delegate', userTokenAccount]),
+          spender: spender.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([spender])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'AllowanceSpent');
+      expectKey(event.allowance, allowance);
+      expectKey(event.spender, spender.publicKey);
+      expectKey(event.destination, destination);
+      expectAmount(event.amount, 400_000);
+      expectAmount(event.spentInPeriod, 400_000);
+      expectAmount(event.totalSpent, 400_000);
+    });
+
+    it('AllowanceRevoked', async () => {
+      const signature = await program.methods
+        .revokeAllowance()
+        .accountsPartial({
+          allowance,
+          counter: pda(program, ['allowance-counter', userTokenAccount]),
+          tokenAccount: userTokenAccount,
+          delegate: pda(program, ['allowance-delegate', userTokenAccount]),
+          owner: user.publicKey,
+          tokenProgram,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'AllowanceRevoked');
+      expectKey(event.allowance, allowance);
+      expectKey(event.owner, user.publicKey);
+      expectKey(event.spender, spender.publicKey);
+      expectAmount(event.totalSpent, 400_000);
+      expectAmount(event.openAllowances, 0);
+    });
+  });
+
+  describe('vesting', () => {
+    const beneficiary = Keypair.generate();
+
+    // Fund a schedule from the user, treasury is the user's own account
+    async function createVesting(id: number, startTs: number, revocable: boolean) {
+      const schedule = pda(program, ['vesting', jdh.mint, beneficiary.publicKey, u64Seed(id)]);
+      const vault = pda(program, ['vesting-vault', schedule]);
+      const signature = await program.methods
+        .createVesting(new BN(id), new BN(1_000_000), new BN(startTs), new BN(startTs), new BN(1_000), null, revocable)
+        .accountsPartial({
+          schedule,
+          vault,
+          mint: jdh.mint,
+          beneficiary: beneficiary.publicKey,
+          creatorTokenAccount: userTokenAccount,
+          treasury: userTokenAccount,
+          creator: user.publicKey,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+      return { schedule, vault, signature };
+    }
+
+    it('VestingCreated and VestedClaimed', async () => {
+      // Started long enough ago to be fully vested
+      const id = nextId++;
+      const startTs = (await chainTime(connection)) - 2_000;
+      const { schedule, vault, signature } = await createVesting(id, startTs, false);
+
+      const created = findEvent(await eventsOf(program, signature), 'VestingCreated');
+      expectKey(created.schedule, schedule);
+      expectAmount(created.id, id);
+      expectKey(created.creator, user.publicKey);
+      expectKey(created.beneficiary, beneficiary.publicKey);
+      expectAmount(created.totalAmount, 1_000_000);
+      expectAmount(created.startTs, startTs);
+      expectAmount(created.cliffTs, startTs);
+      expectAmount(created.duration, 1_000);
+      expectAmount(created.period, 0);
+      expect(created.revocable).to.equal(false);
+
+      const claimed = await program.methods
+        .claimVested()
+        .accountsPartial({
+          schedule,
+          vault,
+          mint: jdh.mint,
+          beneficiaryTokenAccount: await tokenAccount(jdh.mint, beneficiary.publicKey, tokenProgram),
+          beneficiary: beneficiary.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([beneficiary])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, claimed), 'VestedClaimed');
+      expectKey(event.schedule, schedule);
+      expectKey(event.beneficiary, beneficiary.publicKey);
+      expectAmount(event.amount, 1_000_000);
+      expectAmount(event.claimedAmount, 1_000_000);
+    });
+
+    it('VestingRevoked', async () => {
+      // Not started yet, so all of it is unvested
+      const startTs = (await chainTime(connection)) + 3_600;
+      const { schedule, vault } = await createVesting(nextId++, startTs, true);
+
+      const signature = await program.methods
+        .revokeVesting()
+        .accountsPartial({
+          schedule,
+          vault,
+          mint: jdh.mint,
+          treasury: userTokenAccount,
+          creator: user.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'VestingRevoked');
+      expectKey(event.schedule, schedule);
+      expectKey(event.creator, user.publicKey);
+      expectKey(event.treasury, userTokenAccount);
+      expectAmount(event.unvestedReturned, 1_000_000);
+    });
+  });
+
+  describe('airdrop', () => {
+    const claimant = Keypair.generate();
+    const CLAIM = 400_000;
+    let distributor: PublicKey;
+    let vault: PublicKey;
+    let expiresAt: number;
+
+    it('DistributorCreated', async () => {
+      // Single-leaf tree: the root is the leaf itself and the proof is empty
+      const leaf = createHash('sha256')
+        .update(Buffer.from([0]))
+        .update(u64Seed(0))
+        .update(claimant.publicKey.toBuffer())
+        .update(u64Seed(CLAIM))
+        .digest();
+      const id = nextId++;
+      distributor = pda(program, ['distributor', jdh.mint, u64Seed(id)]);
+      vault = pda(program, ['distributor-vault', distributor]);
+      expiresAt = (await chainTime(connection)) + 10;
+
+      const signature = await program.methods
+        .createDistributor(new BN(id), Array.from(leaf), new BN(1_000_000), new BN(1), new BN(expiresAt))
+        .accountsPartial({
+          distributor,
+          vault,
+          mint: jdh.mint,
+          authorityTokenAccount: userTokenAccount,
+          authority: user.publicKey,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'DistributorCreated');
+      expectKey(event.distributor, distributor);
+      expectAmount(event.id, id);
+      expectKey(event.mint, jdh.mint);
+      expectKey(event.authority, user.publicKey);
+      expect(event.merkleRoot).to.deep.equal(Array.from(leaf));
+      expectAmount(event.totalAmount, 1_000_000);
+      expectAmount(event.numClaimants, 1);
+      expectAmount(event.expiresAt, expiresAt);
+    });
+
+    it('AirdropClaimed', async () => {
+      const signature = await program.methods
+        .claimAirdrop(new BN(0), new BN(CLAIM), [])
+        .accountsPartial({
+          distributor,
+          vault,
+          mint: jdh.mint,
+          claimantTokenAccount: await tokenAccount(jdh.mint, claimant.publicKey, tokenProgram),
+          claimant: claimant.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([claimant])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'AirdropClaimed');
+      expectKey(event.distributor, distributor);
+      expectKey(event.claimant, claimant.publicKey);
+      expectAmount(event.index, 0);
+      expectAmount(event.amount, CLAIM);
+      expectAmount(event.totalClaimed, CLAIM);
+      expectAmount(event.numClaimed, 1);
+    });
+
+    it('AirdropClawedBack', async () => {
+      await waitForChainTime(connection, expiresAt);
+
+      const signature = await program.methods
+        .clawback()
+        .accountsPartial({
+          distributor,
+          vault,
+          mint: jdh.mint,
+          destination: userTokenAccount,
+          authority: user.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'AirdropClawedBack');
+      expectKey(event.distributor, distributor);
+      expectKey(event.authority, user.publicKey);
+      expectAmount(event.amount, 1_000_000 - CLAIM);
+    });
+  });
+
+  describe('escrow', () => {
+    const recipient = Keypair.generate().publicKey;
+
+    async function createEscrow(arbiter: PublicKey | null) {
+      const id = nextId++;
+      const escrow = pda(program, ['escrow', user.publicKey, u64Seed(id)]);
+      const vault = pda(program, ['escrow-vault', escrow]);
+      const signature = await program.methods
+        .createEscrow(new BN(id), new BN(1_000_000), arbiter, null)
+        .accountsPartial({
+          escrow,
+          vault,
+          mint: jdh.mint,
+          recipient,
+          senderTokenAccount: userTokenAccount,
+          sender: user.publicKey,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+      return { id, escrow, vault, signature };
+    }
+
+    it('EscrowCreated and EscrowReleased', async () => {
+      const { id, escrow, vault, signature } = await createEscrow(null);
+
+      const created = findEvent(await eventsOf(program, signature), 'EscrowCreated');
+      expectKey(created.escrow, escrow);
+      expectAmount(created.id, id);
+      expectKey(created.sender, user.publicKey);
+      expectKey(created.recipient, recipient);
+      expect(created.arbiter).to.equal(null);
+      expect(created.releaseTime).to.equal(null);
+      expectAmount(created.amount, 1_000_000);
+
+      const released = await program.methods
+        .releaseEscrow()
+        .accountsPartial({
+          escrow,
+          vault,
+          mint: jdh.mint,
+          recipientTokenAccount: await tokenAccount(jdh.mint, recipient, tokenProgram),
+          sender: user.publicKey,
+          signer: user.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, released), 'EscrowReleased');
+      expectKey(event.escrow, escrow);
+      expectAmount(event.id, id);
+      expectKey(event.recipient, recipient);
+      expectKey(event.releasedBy, user.publicKey);
+      expectAmount(event.amount, 1_000_000);
+    });
+
+    it('EscrowDisputed and EscrowRefunded', async () => {
+      const { id, escrow, vault, signature } = await createEscrow(wallet);
+      const created = findEvent(await eventsOf(program, signature), 'EscrowCreated');
+      expectKey(created.arbiter, wallet);
+
+      const disputed = await program.methods
+        .disputeEscrow()
+        .accountsPartial({ escrow, signer: user.publicKey, programConfig })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const disputedEvent = findEvent(await eventsOf(program, disputed), 'EscrowDisputed');
+      expectKey(disputedEvent.escrow, escrow);
+      expectAmount(disputedEvent.id, id);
+      expectKey(disputedEvent.disputedBy, user.publicKey);
+
+      const refunded = await program.methods
+        .refundEscrow()
+        .accountsPartial({
+          escrow,
+          vault,
+          mint: jdh.mint,
+          senderTokenAccount: userTokenAccount,
+          sender: user.publicKey,
+          signer: wallet,
+          tokenProgram,
+          programConfig,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, refunded), 'EscrowRefunded');
+      expectKey(event.escrow, escrow);
+      expectAmount(event.id, id);
+      expectKey(event.sender, user.publicKey);
+      expectKey(event.refundedBy, wallet);
+      expectAmount(event.amount, 1_000_000);
+    });
+  });
+
+  describe('stream', () => {
+    const recipient = Keypair.generate();
+    let stream: PublicKey;
+    let vault: PublicKey;
+    let startTime: number;
+
+    it('StreamCreated', async () => {
+      const id = nextId++;
+      stream = pda(program, ['stream', user.publicKey, u64Seed(id)]);
+      vault = pda(program, ['stream-vault', stream]);
+      startTime = (await chainTime(connection)) + 2;
+
+      const signature = await program.methods
+        .createStream(new BN(id), new BN(10), new BN(startTime), new BN(startTime + 100))
+        .accountsPartial({
+          stream,
+          vault,
+          mint: jdh.mint,
+          recipient: recipient.publicKey,
+          senderTokenAccount: userTokenAccount,
+          sender: user.publicKey,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'StreamCreated');
+      expectKey(event.stream, stream);
+      expectAmount(event.id, id);
+      expectKey(event.sender, user.publicKey);
+      expectKey(event.recipient, recipient.publicKey);
+      expectAmount(event.ratePerSecond, 10);
+      expectAmount(event.startTime, startTime);
+      expectAmount(event.endTime, startTime + 100);
+      expectAmount(event.deposited, 1_000);
+    });
+
+    it('StreamWithdrawn', async () => {
+      await waitForChainTime(connection, startTime + 2);
+
+      const signature = await program.methods
+        .withdrawFromStream()
+        .accountsPartial({
+          stream,
+          vault,
+          mint: jdh.mint,
+          recipientTokenAccount: await tokenAccount(jdh.mint, recipient.publicKey, tokenProgram),
+          recipient: recipient.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([recipient])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'StreamWithdrawn');
+      expectKey(event.stream, stream);
+      expectKey(event.recipient, recipient.publicKey);
+      expect(event.amount.toNumber()).to.be.greaterThan(0);
+      expectAmount(event.withdrawn, event.amount.toNumber());
+    });
+
+    it('StreamCanceled', async () => {
+      const { withdrawn } = await program.account.stream.fetch(stream);
+
+      const signature = await program.methods
+        .cancelStream()
+        .accountsPartial({
+          stream,
+          vault,
+          mint: jdh.mint,
+          senderTokenAccount: userTokenAccount,
+          recipientTokenAccount: await tokenAccount(jdh.mint, recipient.publicKey, tokenProgram),
+          sender: user.publicKey,
+          signer: user.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      // The whole deposit is split between what the recipient already took, its accrued rest
+      // and the sender's refund
+      const event = findEvent(await eventsOf(program, signature), 'StreamCanceled');
+      expectKey(event.stream, stream);
+      expectKey(event.canceledBy, user.publicKey);
+      expect(
+        withdrawn.toNumber() + event.recipientAmount.toNumber() + event.senderAmount.toNumber(),
+      ).to.equal(1_000);
+    });
+  });
+
+  describe('staking', () => {
+    const COMPOUND_TIP_BPS = 500;
+    let pool: PublicKey;
+    let stakeVault: PublicKey;
+    let rewardsVault: PublicKey;
+    let vaultAuthority: PublicKey;
+    let userStake: PublicKey;
+
+    it('StakingPoolInitialized', async () => {
+      const initialized = await initializeStakingPool(program, jdh);
+      pool = initialized.pool;
+      stakeVault = initialized.stakeVault;
+      rewardsVault = pda(program, ['rewards-vault', pool]);
+      vaultAuthority = pda(program, ['pool-authority', pool]);
+      userStake = pda(program, ['user-stake', user.publicKey, pool, u64Seed(0)]);
+
+      const event = findEvent(await eventsOf(program, initialized.signature), 'StakingPoolInitialized');
+      expectKey(event.pool, pool);
+      expectKey(event.mint, jdh.mint);
+      expectKey(event.poolAuthority, wallet);
+      expectKey(event.stakeVault, stakeVault);
+      expectKey(event.rewardsVault, rewardsVault);
+      expectAmount(event.rewardRate, 0);
+      expectAmount(event.minStake, 1);
+      expect(event.lockTiers).to.have.length(1);
+      expectAmount(event.lockTiers[0].lockPeriod, 0);
+      expect(event.lockTiers[0].multiplierBps).to.equal(10_000);
+    });
+
+    it('Staked', async () => {
+      const signature = await program.methods
+        .stakeTokens(new BN(0), new BN(2_000_000), 0)
+        .accountsPartial({
+          pool,
+          mint: jdh.mint,
+          counter: pda(program, ['user-stake-counter', user.publicKey, pool]),
+          userStake,
+          userTokenAccount,
+          stakeVault,
+          user: user.publicKey,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'Staked');
+      expectKey(event.pool, pool);
+      expectKey(event.user, user.publicKey);
+      expectAmount(event.positionId, 0);
+      expectAmount(event.amount, 2_000_000);
+      expect(event.tier).to.equal(0);
+      expectAmount(event.userStaked, 2_000_000);
+      expectAmount(event.poolTotalStaked, 2_000_000);
+      expectAmount(event.lockUntil, event.timestamp.toNumber());
+    });
+
+    it('RewardRateUpdated', async () => {
+      const signature = await program.methods
+        .setStakingRewardRate(new BN(1_000))
+        .accountsPartial({ pool, poolAuthority: wallet })
+        .rpc({ commitment: 'confirmed' });
+
+      // Nothing is funded yet, so the window ends right away
+      const event = findEvent(await eventsOf(program, signature), 'RewardRateUpdated');
+      expectKey(event.pool, pool);
+      expectAmount(event.rewardRate, 1_000);
+      expectAmount(event.accRewardPerShare, 0);
+      expectAmount(event.rewardEnd, event.timestamp.toNumber());
+    });
+
+    it('RewardsFunded', async () => {
+      const signature = await program.methods
+        .fundRewards(new BN(1_000_000))
+        .accountsPartial({
+          pool,
+          mint: jdh.mint,
+          rewardsVault,
+          funderTokenAccount: userTokenAccount,
+          funder: user.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'RewardsFunded');
+      expectKey(event.pool, pool);
+      expectKey(event.funder, user.publicKey);
+      expectAmount(event.amount, 1_000_000);
+      expectAmount(event.remainingRewards, 1_000_000);
+      expectAmount(event.rewardEnd, event.timestamp.toNumber() + 1_000);
+    });
+
+    it('CompoundTipUpdated', async () => {
+      const signature = await program.methods
+        .setCompoundTip(COMPOUND_TIP_BPS)
+        .accountsPartial({ pool, poolAuthority: wallet })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'CompoundTipUpdated');
+      expectKey(event.pool, pool);
+      expect(event.compoundTipBps).to.equal(COMPOUND_TIP_BPS);
+    });
+
+    it('AutoCompoundSet', async () => {
+      const signature = await program.methods
+        .setAutoCompound(true)
+        .accountsPartial({ userStake, user: user.publicKey })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'AutoCompoundSet');
+      expectKey(event.pool, pool);
+      expectKey(event.user, user.publicKey);
+      expectAmount(event.positionId, 0);
+      expect(event.enabled).to.equal(true);
+    });
+
+    it('RewardsCompounded', async () => {
+      await waitForChainTime(connection, (await chainTime(connection)) + 1);
+
+      // A third-party crank, paid the tip
+      const signature = await program.methods
+        .compoundRewards()
+        .accountsPartial({
+          pool,
+          mint: jdh.mint,
+          userStake,
+          stakeVault,
+          rewardsVault,
+          vaultAuthority,
+          crankerTokenAccount: await tokenAccount(jdh.mint, wallet, tokenProgram),
+          cranker: wallet,
+          tokenProgram,
+          programConfig,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'RewardsCompounded');
+      const rewards = event.amount.toNumber() + event.tip.toNumber();
+      expectKey(event.pool, pool);
+      expectKey(event.user, user.publicKey);
+      expectAmount(event.positionId, 0);
+      expectKey(event.cranker, wallet);
+      expect(rewards).to.be.greaterThan(0);
+      expectAmount(event.tip, Math.floor((rewards * COMPOUND_TIP_BPS) / 10_000));
+      expectAmount(event.userStaked, 2_000_000 + event.amount.toNumber());
+      expectAmount(event.poolTotalStaked, 2_000_000 + event.amount.toNumber());
+    });
+
+    it('StakingRewardsClaimed', async () => {
+      const before = await program.account.userStake.fetch(userStake);
+      await waitForChainTime(connection, (await chainTime(connection)) + 1);
+
+      const signature = await program.methods
+        .claimStakingRewards()
+        .accountsPartial({
+          pool,
+          mint: jdh.mint,
+          userStake,
+          userTokenAccount,
+          rewardsVault,
+          vaultAuthority,
+          user: user.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'StakingRewardsClaimed');
+      expectKey(event.pool, pool);
+      expectKey(event.user, user.publicKey);
+      expectAmount(event.positionId, 0);
+      expect(event.amount.toNumber()).to.be.greaterThan(0);
+      expectAmount(event.rewardsEarned, before.rewardsEarned.toNumber() + event.amount.toNumber());
+    });
+
+    it('Unstaked', async () => {
+      const before = await program.account.userStake.fetch(userStake);
+
+      const signature = await program.methods
+        .unstakeTokens(before.amount)
+        .accountsPartial({
+          pool,
+          mint: jdh.mint,
+          userStake,
+          userTokenAccount,
+          stakeVault,
+          rewardsVault,
+          vaultAuthority,
+          user: user.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const after = await program.account.userStake.fetch(userStake);
+      const event = findEvent(await eventsOf(program, signature), 'Unstaked');
+      expectKey(event.pool, pool);
+      expectKey(event.user, user.publicKey);
+      expectAmount(event.positionId, 0);
+      expectAmount(event.amount, before.amount.toNumber());
+      expectAmount(event.rewards, after.rewardsEarned.toNumber() - before.rewardsEarned.toNumber());
+      expectAmount(event.pendingRewards, 0);
+      expectAmount(event.userStaked, 0);
+      expectAmount(event.poolTotalStaked, 0);
+    });
+
+    it('UserStakeClosed', async () => {
+      const { rewardsEarned } = await program.account.userStake.fetch(userStake);
+
+      const signature = await program.methods
+        .closeUserStake()
+        .accountsPartial({
+          counter: pda(program, ['user-stake-counter', user.publicKey, pool]),
+          userStake,
+          user: user.publicKey,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'UserStakeClosed');
+      expectKey(event.pool, pool);
+      expectKey(event.user, user.publicKey);
+      expectAmount(event.positionId, 0);
+      expectAmount(event.rewardsEarned, rewardsEarned.toNumber());
+    });
+  });
+
+  describe('governance', () => {
+    const id = nextId++;
+    let proposal: PublicKey;
+    let votingEndsAt: number;
+
+    it('ProposalCreated', async () => {
+      proposal = pda(program, ['proposal', u64Seed(id)]);
+
+      const signature = await program.methods
+        .createProposal(new BN(id), 'Raise staking rewards', 'Double the reward rate', new BN(4), new BN(1_000))
+        .accountsPartial({
+          proposal,
+          proposer: user.publicKey,
+          systemProgram: SystemProgram.programId,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'ProposalCreated');
+      votingEndsAt = event.votingEndsAt.toNumber();
+      expectKey(event.proposal, proposal);
+      expectAmount(event.id, id);
+      expectKey(event.proposer, user.publicKey);
+      expect(event.title).to.equal('Raise staking rewards');
+      expect(votingEndsAt).to.equal(event.timestamp.toNumber() + 4);
+      expectAmount(event.minVotingPower, 1_000);
+    });
+
+    it('VoteCast', async () => {
+      const signature = await program.methods
+        .voteProposal({ for: {} }, new BN(1_000))
+        .accountsPartial({
+          proposal,
+          vote: pda(program, ['vote', user.publicKey, proposal]),
+          userTokenAccount,
+          voter: user.publicKey,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'VoteCast');
+      expectKey(event.proposal, proposal);
+      expectKey(event.voter, user.publicKey);
+      expect(event.voteType).to.deep.equal({ for: {} });
+      expectAmount(event.votingPower, 1_000);
+      expectAmount(event.votesFor, 1_000);
+      expectAmount(event.votesAgainst, 0);
+      expectAmount(event.totalVotes, 1_000);
+    });
+
+    it('ProposalExecuted', async () => {
+      await waitForChainTime(connection, votingEndsAt);
+
+      const signature = await program.methods
+        .executeProposal()
+        .accountsPartial({ proposal, executor: wallet, programConfig })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'ProposalExecuted');
+      expectKey(event.proposal, proposal);
+      expectAmount(event.id, id);
+      expectKey(event.executor, wallet);
+      expectAmount(event.votesFor, 1_000);
+      expectAmount(event.votesAgainst, 0);
+    });
+
+    it('VoteReceiptClosed', async () => {
+      const signature = await program.methods
+        .closeVoteReceipt()
+        .accountsPartial({
+          proposal,
+          vote: pda(program, ['vote', user.publicKey, proposal]),
+          voter: user.publicKey,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'VoteReceiptClosed');
+      expectKey(event.proposal, proposal);
+      expectKey(event.voter, user.publicKey);
+    });
+  });
+
+  describe('mining', () => {
+    const ENTRY_FEE_CAP = 100_000;
+    let vault: PublicKey;
+    let vaultTokenAccount: PublicKey;
+    const userDeposit = () => pda(program, ['user-mining-deposit', user.publicKey]);
+
+    it('MiningVaultInitialized', async () => {
+      const initialized = await initializeMiningVault(program, jdh.mint, tokenProgram, ENTRY_FEE_CAP);
+      vault = initialized.vault;
+      vaultTokenAccount = initialized.vaultTokenAccount;
+
+      const event = findEvent(await eventsOf(program, initialized.signature), 'MiningVaultInitialized');
+      expectKey(event.vault, vault);
+      expectKey(event.mint, jdh.mint);
+      expectKey(event.poolAuthority, wallet);
+      expectAmount(event.entryFeeCap, ENTRY_FEE_CAP);
+    });
+
+    it('MiningDeposited', async () => {
+      const signature = await program.methods
+        .depositMining(new BN(3_000_000))
+        .accountsPartial({
+          vault,
+          mint: jdh.mint,
+          userDeposit: userDeposit(),
+          user: user.publicKey,
+          userTokenAccount,
+          vaultTokenAccount,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'MiningDeposited');
+      expectKey(event.vault, vault);
+      expectKey(event.user, user.publicKey);
+      expectAmount(event.amount, 3_000_000);
+      expectAmount(event.depositAmount, 3_000_000);
+      expect(event.tier).to.deep.equal({ platinum: {} });
+      expectAmount(event.totalDeposited, 3_000_000);
+    });
+
+    it('EntryFeePaid', async () => {
+      const signature = await program.methods
+        .payMiningEntryFee(new BN(50_000))
+        .accountsPartial({
+          vault,
+          mint: jdh.mint,
+          userDeposit: userDeposit(),
+          user: user.publicKey,
+          userTokenAccount,
+          poolTokenAccount: await tokenAccount(jdh.mint, wallet, tokenProgram),
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'EntryFeePaid');
+      expectKey(event.vault, vault);
+      expectKey(event.user, user.publicKey);
+      expectAmount(event.fee, 50_000);
+      expectAmount(event.totalEntryFeesPaid, 50_000);
+    });
+
+    it('MiningWithdrawn', async () => {
+      const signature = await program.methods
+        .withdrawMining(new BN(3_000_000))
+        .accountsPartial({
+          vault,
+          mint: jdh.mint,
+          userDeposit: userDeposit(),
+          user: user.publicKey,
+          userTokenAccount,
+          vaultTokenAccount,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'MiningWithdrawn');
+      expectKey(event.vault, vault);
+      expectKey(event.user, user.publicKey);
+      expectAmount(event.amount, 3_000_000);
+      expectAmount(event.depositAmount, 0);
+      expect(event.tier).to.deep.equal({ bronze: {} });
+      expectAmount(event.totalDeposited, 0);
+    });
+
+    it('MiningDepositClosed', async () => {
+      const signature = await program.methods
+        .closeMiningDeposit()
+        .accountsPartial({ userDeposit: userDeposit(), user: user.publicKey })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'MiningDepositClosed');
+      expectKey(event.user, user.publicKey);
+      expectAmount(event.totalEntryFeesPaid, 50_000);
+    });
+  });
+
+  describe('multisig', () => {
+    const third = Keypair.generate().publicKey;
+    let multisig: PublicKey;
+    let multisigSigner: PublicKey;
+    let transaction: PublicKey;
+    let setMembers: anchor.web3.TransactionInstruction;
+
+    it('MultisigCreated', async () => {
+      const id = nextId++;
+      multisig = pda(program, ['multisig', user.publicKey, u64Seed(id)]);
+      multisigSigner = pda(program, ['multisig-signer', multisig]);
+      const members = [user.publicKey, wallet];
+
+      const signature = await program.methods
+        .createMultisig(new BN(id), members, 2)
+        .accountsPartial({
+          multisig,
+          multisigSigner,
+          creator: user.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'MultisigCreated');
+      expectKey(event.multisig, multisig);
+      expectKey(event.signer, multisigSigner);
+      expect(event.threshold).to.equal(2);
+      expect(event.members.map((member: PublicKey) => member.toBase58())).to.deep.equal(
+        members.map((member) => member.toBase58()),
+      );
+    });
+
+    it('MultisigTransactionProposed', async () => {
+      // The multisig proposes adding a third member to itself
+      setMembers = await program.methods
+        .setMultisigMembers([user.publicKey, wallet, third], 2)
+        .accountsPartial({ multisig, multisigSigner })
+        .instruction();
+      transaction = pda(program, ['multisig-tx', multisig, u64Seed(0)]);
+
+      const signature = await program.methods
+        .proposeMultisigTransaction(
+          program.programId,
+          setMembers.keys.map(({ pubkey, isSigner, isWritable }) => ({ pubkey, isSigner, isWritable })),
+          setMembers.data,
+        )
+        .accountsPartial({
+          multisig,
+          transaction,
+          proposer: user.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'MultisigTransactionProposed');
+      expectKey(event.multisig, multisig);
+      expectKey(event.transaction, transaction);
+      expectAmount(event.index, 0);
+      expectKey(event.proposer, user.publicKey);
+      expectKey(event.programId, program.programId);
+    });
+
+    it('MultisigTransactionApproved', async () => {
+      const signature = await program.methods
+        .approveMultisigTransaction()
+        .accountsPartial({ multisig, transaction, member: wallet })
+        .rpc({ commitment: 'confirmed' });
+
+      const event = findEvent(await eventsOf(program, signature), 'MultisigTransactionApproved');
+      expectKey(event.multisig, multisig);
+      expectKey(event.transaction, transaction);
+      expectKey(event.member, wallet);
+      expect(event.approvals).to.equal(2);
+    });
+
+    it('MultisigTransactionExecuted and MultisigMembersChanged', async () => {
+      // The signer PDA is signed for by the program, not by the transaction
+      const signature = await program.methods
+        .executeMultisigTransaction()
+        .accountsPartial({ multisig, multisigSigner, transaction, executor: wallet })
+        .remainingAccounts([
+          ...setMembers.keys.map(({ pubkey, isWritable }) => ({ pubkey, isWritable, isSigner: false })),
+          { pubkey: program.programId, isWritable: false, isSigner: false },
+        ])
+        .rpc({ commitment: 'confirmed' });
+
+      const events = await eventsOf(program, signature);
+      const executed = findEvent(events, 'MultisigTransactionExecuted');
+      expectKey(executed.multisig, multisig);
+      expectKey(executed.transaction, transaction);
+      expectKey(executed.executor, wallet);
+
+      const changed = findEvent(events, 'MultisigMembersChanged');
+      expectKey(changed.multisig, multisig);
+      expect(changed.threshold).to.equal(2);
+      expect(changed.members.map((member: PublicKey) => member.toBase58())).to.deep.equal(
+        [user.publicKey, wallet, third].map((member) => member.toBase58()),
+      );
+      expect(changed.membersVersion).to.equal(1);
+    });
+  });
+});
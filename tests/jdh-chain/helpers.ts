@@ -0,0 +1,260 @@
+import * as anchor from '@coral-xyz/anchor';
+import { BN, Program } from '@coral-xyz/anchor';
+import { getAccount, getOrCreateAssociatedTokenAccount } from '@solana/spl-token';
+import { Connection, Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from '@solana/web3.js';
+import { expect } from 'chai';
+
+import { JdhChain } from '../../target/types/jdh_chain';
+
+const BPF_LOADER_UPGRADEABLE = new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111');
+
+export const DECIMALS = 6;
+
+export type Seed = string | PublicKey | Buffer;
+
+// A JDH mint created through initialize_mint, with the provider wallet as authority and minter
+export interface JdhMint {
+  mint: PublicKey;
+  config: PublicKey;
+  tokenProgram: PublicKey;
+  mintSignature: string;
+  minterSignature: string;
+}
+
+export function provider(): anchor.AnchorProvider {
+  return anchor.getProvider() as anchor.AnchorProvider;
+}
+
+export function payer(): Keypair {
+  return (provider().wallet as anchor.Wallet).payer;
+}
+
+export function u64Seed(value: number): Buffer {
+  return new BN(value).toArrayLike(Buffer, 'le', 8);
+}
+
+export function pda(program: Program<JdhChain>, seeds: Seed[]): PublicKey {
+  const buffers = seeds.map((seed) =>
+    typeof seed === 'string' ? Buffer.from(seed) : seed instanceof PublicKey ? seed.toBuffer() : seed,
+  );
+  return PublicKey.findProgramAddressSync(buffers, program.programId)[0];
+}
+
+// Unix time as seen by the validator
+export async function chainTime(connection: Connection): Promise<number> {
+  const blockTime = await connection.getBlockTime(await connection.getSlot('confirmed'));
+  return blockTime ?? Math.floor(Date.now() / 1000);
+}
+
+// Poll until the validator clock has passed a unix time
+export async function waitForChainTime(connection: Connection, unixTime: number): Promise<void> {
+  while ((await chainTime(connection)) <= unixTime) {
+    await new Promise((resolve) => setTimeout(resolve, 400));
+  }
+}
+
+export async function fundedKeypair(connection: Connection): Promise<Keypair> {
+  const keypair = Keypair.generate();
+  const signature = await connection.requestAirdrop(keypair.publicKey, 2 * LAMPORTS_PER_SOL);
+  const latest = await connection.getLatestBlockhash();
+  await connection.confirmTransaction({ signature, ...latest }, 'confirmed');
+  return keypair;
+}
+
+// Program config is a singleton, initialize it once per validator (provider wallet is upgrade authority)
+export async function ensureProgramConfig(program: Program<JdhChain>): Promise<PublicKey> {
+  const programConfig = pda(program, ['program-config']);
+  if (await provider().connection.getAccountInfo(programConfig)) {
+    return programConfig;
+  }
+
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE,
+  );
+  await program.methods
+    .initializeProgramConfig(provider().wallet.publicKey)
+    .accountsPartial({
+      programConfig,
+      program: program.programId,
+      programData,
+      admin: provider().wallet.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc({ commitment: 'confirmed' });
+  return programConfig;
+}
+
+// Token-2022 transfer fee for createJdhMint, as taken by initialize_mint
+export interface TransferFee {
+  transferFeeBps: number;
+  maximumFee: BN;
+}
+
+export async function createJdhMint(
+  program: Program<JdhChain>,
+  tokenProgram: PublicKey,
+  transferFee: TransferFee | null = null,
+): Promise<JdhMint> {
+  const wallet = provider().wallet.publicKey;
+  const mint = Keypair.generate();
+  const config = pda(program, ['token-config', mint.publicKey]);
+
+  const mintSignature = await program.methods
+    .initializeMint(
+      DECIMALS,
+      new BN(1_000_000_000_000_000),
+      new BN(86_400),
+      new BN(100_000_000_000_000),
+      0,
+      transferFee,
+    )
+    .accountsPartial({
+      config,
+      mint: mint.publicKey,
+      mintAuthority: pda(program, ['mint-authority', mint.publicKey]),
+      freezeAuthority: pda(program, ['freeze-authority', mint.publicKey]),
+      authority: wallet,
+      tokenProgram,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([mint])
+    .rpc({ commitment: 'confirmed' });
+
+  const minterSignature = await program.methods
+    .addMinter(new BN(100_000_000_000_000), new BN(100_000_000_000_000), new BN(86_400))
+    .accountsPartial({
+      config,
+      minterAllowance: pda(program, ['minter', config, wallet]),
+      minter: wallet,
+      authority: wallet,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc({ commitment: 'confirmed' });
+
+  return { mint: mint.publicKey, config, tokenProgram, mintSignature, minterSignature };
+}
+
+export async function tokenAccount(
+  mint: PublicKey,
+  owner: PublicKey,
+  tokenProgram: PublicKey,
+): Promise<PublicKey> {
+  const account = await getOrCreateAssociatedTokenAccount(
+    provider().connection,
+    payer(),
+    mint,
+    owner,
+    true,
+    'confirmed',
+    undefined,
+    tokenProgram,
+  );
+  return account.address;
+}
+
+// Mint JDH to an owner's associated token account through mint_tokens
+export async function mintJdh(
+  program: Program<JdhChain>,
+  jdh: JdhMint,
+  owner: PublicKey,
+  amount: number,
+): Promise<{ account: PublicKey; signature: string }> {
+  const wallet = provider().wallet.publicKey;
+  const account = await tokenAccount(jdh.mint, owner, jdh.tokenProgram);
+  const signature = await program.methods
+    .mintTokens(new BN(amount))
+    .accountsPartial({
+      config: jdh.config,
+      mint: jdh.mint,
+      to: account,
+      mintAuthority: pda(program, ['mint-authority', jdh.mint]),
+      minterAllowance: pda(program, ['minter', jdh.config, wallet]),
+      minter: wallet,
+      tokenProgram: jdh.tokenProgram,
+      programConfig: pda(program, ['program-config']),
+    })
+    .rpc({ commitment: 'confirmed' });
+  return { account, signature };
+}
+
+export async function balanceOf(account: PublicKey, tokenProgram: PublicKey): Promise<bigint> {
+  return (await getAccount(provider().connection, account, 'confirmed', tokenProgram)).amount;
+}
+
+export async function initializeStakingPool(
+  program: Program<JdhChain>,
+  jdh: JdhMint,
+): Promise<{ pool: PublicKey; stakeVault: PublicKey; signature: string }> {
+  const pool = pda(program, ['staking-pool', jdh.mint]);
+  const stakeVault = pda(program, ['stake-vault', pool]);
+  const signature = await program.methods
+    .initializeStakingPool(new BN(0), new BN(1), [{ lockPeriod: new BN(0), multiplierBps: 10_000 }])
+    .accountsPartial({
+      config: jdh.config,
+      pool,
+      mint: jdh.mint,
+      vaultAuthority: pda(program, ['pool-authority', pool]),
+      stakeVault,
+      rewardsVault: pda(program, ['rewards-vault', pool]),
+      authority: provider().wallet.publicKey,
+      tokenProgram: jdh.tokenProgram,
+      systemProgram: SystemProgram.programId,
+      programConfig: pda(program, ['program-config']),
+    })
+    .rpc({ commitment: 'confirmed' });
+  return { pool, stakeVault, signature };
+}
+
+// Mining vault for a mint plus a token account owned by the vault PDA
+export async function initializeMiningVault(
+  program: Program<JdhChain>,
+  mint: PublicKey,
+  tokenProgram: PublicKey,
+  entryFeeCap = 0,
+): Promise<{ vault: PublicKey; vaultTokenAccount: PublicKey; signature: string }> {
+  const vault = pda(program, ['mining-vault', mint]);
+  const signature = await program.methods
+    .initializeMiningVault(new BN(entryFeeCap))
+    .accountsPartial({
+      vault,
+      mint,
+      poolAuthority: provider().wallet.publicKey,
+      systemProgram: SystemProgram.programId,
+      programConfig: pda(program, ['program-config']),
+    })
+    .rpc({ commitment: 'confirmed' });
+  return { vault, vaultTokenAccount: await tokenAccount(mint, vault, tokenProgram), signature };
+}
+
+// Events emitted by a confirmed transaction, decoded from its logs with the program IDL
+export async function eventsOf(
+  program: Program<JdhChain>,
+  signature: string,
+): Promise<anchor.Event[]> {
+  const transaction = await provider().connection.getTransaction(signature, {
+    commitment: 'confirmed',
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(program.programId, program.coder);
+  return Array.from(parser.parseLogs(transaction?.meta?.logMessages ?? []));
+}
+
+// Data of the named event (IDL names are camelCased by the client, so compare loosely)
+export function findEvent(events: anchor.Event[], name: string): any {
+  const normalize = (eventName: string) => eventName.charAt(0).toLowerCase() + eventName.slice(1);
+  const event = events.find((candidate) => normalize(candidate.name) === normalize(name));
+  expect(event, `${name} event`).to.not.equal(undefined);
+  return event!.data;
+}
+
+// Await a transaction that must fail with the named program or constraint error
+export async function expectError(transaction: Promise<unknown>, errorName: string): Promise<void> {
+  try {
+    await transaction;
+  } catch (err) {
+    expect(String(err)).to.include(errorName);
+    return;
+  }
+  expect.fail(`expected ${errorName}`);
+}
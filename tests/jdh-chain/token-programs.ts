@@ -0,0 +1,437 @@
+import * as anchor from '@coral-xyz/anchor';
+import { BN, Program } from '@coral-xyz/anchor';
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair, PublicKey, SystemProgram } from '@solana/web3.js';
+import { expect } from 'chai';
+
+import { JdhChain } from '../../target/types/jdh_chain';
+import {
+  balanceOf,
+  chainTime,
+  createJdhMint,
+  ensureProgramConfig,
+  eventsOf,
+  expectError,
+  findEvent,
+  fundedKeypair,
+  initializeMiningVault,
+  initializeStakingPool,
+  JdhMint,
+  mintJdh,
+  pda,
+  provider,
+  tokenAccount,
+  u64Seed,
+} from './helpers';
+
+// stake, deposit_mining and transfer_tokens go through the token interface, so they
+// must behave the same for a classic SPL Token mint and a Token-2022 JDH mint
+describe('jdh-chain token programs', () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.JdhChain as Program<JdhChain>;
+  const connection = anchor.getProvider().connection;
+
+  let programConfig: PublicKey;
+
+  before(async () => {
+    programConfig = await ensureProgramConfig(program);
+  });
+
+  const tokenPrograms: [string, PublicKey][] = [
+    ['classic SPL Token mint', TOKEN_PROGRAM_ID],
+    ['Token-2022 JDH mint', TOKEN_2022_PROGRAM_ID],
+  ];
+
+  for (const [label, tokenProgram] of tokenPrograms) {
+    describe(label, () => {
+      let jdh: JdhMint;
+      let user: Keypair;
+      let userTokenAccount: PublicKey;
+
+      before(async () => {
+        jdh = await createJdhMint(program, tokenProgram);
+        user = await fundedKeypair(connection);
+        userTokenAccount = (await mintJdh(program, jdh, user.publicKey, 10_000_000)).account;
+      });
+
+      it('transfer_tokens moves the full amount before a fee config exists', async () => {
+        const to = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+
+        await program.methods
+          .transferTokens(new BN(1_000_000))
+          .accountsPartial({
+            feeConfig: pda(program, ['fee-config', jdh.mint]),
+            mint: jdh.mint,
+            from: userTokenAccount,
+            to,
+            treasury: null,
+            authority: user.publicKey,
+            tokenProgram,
+            programConfig,
+          })
+          .signers([user])
+          .rpc({ commitment: 'confirmed' });
+
+        expect(await balanceOf(to, tokenProgram)).to.equal(1_000_000n);
+        expect(await balanceOf(userTokenAccount, tokenProgram)).to.equal(9_000_000n);
+      });
+
+      it('stake_tokens credits the position and the pool vault', async () => {
+        const { pool, stakeVault } = await initializeStakingPool(program, jdh);
+        const userStake = pda(program, ['user-stake', user.publicKey, pool, u64Seed(0)]);
+
+        await program.methods
+          .stakeTokens(new BN(0), new BN(2_000_000), 0)
+          .accountsPartial({
+            pool,
+            mint: jdh.mint,
+            counter: pda(program, ['user-stake-counter', user.publicKey, pool]),
+            userStake,
+            userTokenAccount,
+            stakeVault,
+            user: user.publicKey,
+            tokenProgram,
+            systemProgram: SystemProgram.programId,
+            programConfig,
+          })
+          .signers([user])
+          .rpc({ commitment: 'confirmed' });
+
+        const poolAccount = await program.account.stakingPool.fetch(pool);
+        const stakeAccount = await program.account.userStake.fetch(userStake);
+        expect(poolAccount.totalStaked.toNumber()).to.equal(2_000_000);
+        expect(stakeAccount.amount.toNumber()).to.equal(2_000_000);
+        expect(await balanceOf(stakeVault, tokenProgram)).to.equal(2_000_000n);
+      });
+
+      it('deposit_mining credits the deposit and the vault', async () => {
+        const { vault, vaultTokenAccount } = await initializeMiningVault(program, jdh.mint, tokenProgram);
+        const userDeposit = pda(program, ['user-mining-deposit', user.publicKey]);
+
+        await program.methods
+          .depositMining(new BN(3_000_000))
+          .accountsPartial({
+            vault,
+            mint: jdh.mint,
+            userDeposit,
+            user: user.publicKey,
+            userTokenAccount,
+            vaultTokenAccount,
+            tokenProgram,
+            systemProgram: SystemProgram.programId,
+            programConfig,
+          })
+          .signers([user])
+          .rpc({ commitment: 'confirmed' });
+
+        const depositAccount = await program.account.userMiningDeposit.fetch(userDeposit);
+        const vaultAccount = await program.account.miningVault.fetch(vault);
+        expect(depositAccount.depositAmount.toNumber()).to.equal(3_000_000);
+        expect(vaultAccount.totalDeposited.toNumber()).to.equal(3_000_000);
+        expect(await balanceOf(vaultTokenAccount, tokenProgram)).to.equal(3_000_000n);
+      });
+    });
+  }
+
+  // Once a mint has a fee config the protocol fee cannot be skipped by leaving accounts out
+  describe('protocol fee config', () => {
+    const tokenProgram = TOKEN_2022_PROGRAM_ID;
+    let jdh: JdhMint;
+    let user: Keypair;
+    let userTokenAccount: PublicKey;
+    let feeConfig: PublicKey;
+    let treasury: PublicKey;
+
+    const transfer = (accounts: { feeConfig: PublicKey; treasury: PublicKey | null; to: PublicKey }) =>
+      program.methods
+        .transferTokens(new BN(1_000_000))
+        .accountsPartial({
+          feeConfig: accounts.feeConfig,
+          mint: jdh.mint,
+          from: userTokenAccount,
+          to: accounts.to,
+          treasury: accounts.treasury,
+          authority: user.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+    before(async () => {
+      jdh = await createJdhMint(program, tokenProgram);
+      user = await fundedKeypair(connection);
+      userTokenAccount = (await mintJdh(program, jdh, user.publicKey, 10_000_000)).account;
+      feeConfig = pda(program, ['fee-config', jdh.mint]);
+      treasury = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+
+      await program.methods
+        .initializeFeeConfig(100, new BN(1_000_000))
+        .accountsPartial({
+          config: jdh.config,
+          feeConfig,
+          mint: jdh.mint,
+          treasury,
+          authority: provider().wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: 'confirmed' });
+    });
+
+    it('rejects an account other than the fee-config PDA', async () => {
+      const to = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+      await expectError(transfer({ feeConfig: Keypair.generate().publicKey, treasury, to }), 'ConstraintSeeds');
+    });
+
+    it('rejects a transfer that leaves out the treasury', async () => {
+      const to = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+      await expectError(transfer({ feeConfig, treasury: null, to }), 'MissingTreasury');
+    });
+
+    it('rejects a treasury other than the configured one', async () => {
+      const to = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+      const other = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+      await expectError(transfer({ feeConfig, treasury: other, to }), 'InvalidTreasury');
+    });
+
+    it('rejects a batch transfer that leaves out the treasury', async () => {
+      const to = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+      await expectError(
+        program.methods
+          .batchTransfer([new BN(1_000_000)])
+          .accountsPartial({
+            feeConfig,
+            mint: jdh.mint,
+            from: userTokenAccount,
+            treasury: null,
+            authority: user.publicKey,
+            tokenProgram,
+            programConfig,
+          })
+          .remainingAccounts([{ pubkey: to, isWritable: true, isSigner: false }])
+          .signers([user])
+          .rpc({ commitment: 'confirmed' }),
+        'MissingTreasury',
+      );
+    });
+
+    it('charges the fee when the fee config and treasury are passed', async () => {
+      const to = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+      await transfer({ feeConfig, treasury, to });
+
+      expect(await balanceOf(to, tokenProgram)).to.equal(990_000n);
+      expect(await balanceOf(treasury, tokenProgram)).to.equal(10_000n);
+      const config = await program.account.feeConfig.fetch(feeConfig);
+      expect(config.totalFeesCollected.toNumber()).to.equal(10_000);
+    });
+  });
+
+  // A JDH mint created with a Token-2022 transfer fee: deposits credit the balance delta
+  describe('Token-2022 JDH mint with a transfer fee', () => {
+    const tokenProgram = TOKEN_2022_PROGRAM_ID;
+    const AMOUNT = 1_000_000;
+    const WITHHELD = AMOUNT / 100;
+    const RECEIVED = AMOUNT - WITHHELD;
+
+    let jdh: JdhMint;
+    let user: Keypair;
+    let userTokenAccount: PublicKey;
+
+    before(async () => {
+      jdh = await createJdhMint(program, tokenProgram, {
+        transferFeeBps: 100,
+        maximumFee: new BN(1_000_000_000),
+      });
+      user = await fundedKeypair(connection);
+      userTokenAccount = (await mintJdh(program, jdh, user.publicKey, 10_000_000)).account;
+    });
+
+    it('stake_tokens credits only what arrived after the transfer fee', async () => {
+      const { pool, stakeVault } = await initializeStakingPool(program, jdh);
+      const userStake = pda(program, ['user-stake', user.publicKey, pool, u64Seed(0)]);
+
+      const signature = await program.methods
+        .stakeTokens(new BN(0), new BN(AMOUNT), 0)
+        .accountsPartial({
+          pool,
+          mint: jdh.mint,
+          counter: pda(program, ['user-stake-counter', user.publicKey, pool]),
+          userStake,
+          userTokenAccount,
+          stakeVault,
+          user: user.publicKey,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const poolAccount = await program.account.stakingPool.fetch(pool);
+      const stakeAccount = await program.account.userStake.fetch(userStake);
+      expect(poolAccount.totalStaked.toNumber()).to.equal(RECEIVED);
+      expect(stakeAccount.amount.toNumber()).to.equal(RECEIVED);
+      expect(await balanceOf(stakeVault, tokenProgram)).to.equal(BigInt(RECEIVED));
+
+      const staked = findEvent(await eventsOf(program, signature), 'Staked');
+      expect(staked.amount.toNumber()).to.equal(RECEIVED);
+    });
+
+    it('deposit_mining credits only what arrived after the transfer fee', async () => {
+      const { vault, vaultTokenAccount } = await initializeMiningVault(program, jdh.mint, tokenProgram);
+      const userDeposit = pda(program, ['user-mining-deposit', user.publicKey]);
+
+      const signature = await program.methods
+        .depositMining(new BN(AMOUNT))
+        .accountsPartial({
+          vault,
+          mint: jdh.mint,
+          userDeposit,
+          user: user.publicKey,
+          userTokenAccount,
+          vaultTokenAccount,
+          tokenProgram,
+          systemProgram: SystemProgram.programId,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      const depositAccount = await program.account.userMiningDeposit.fetch(userDeposit);
+      const vaultAccount = await program.account.miningVault.fetch(vault);
+      expect(depositAccount.depositAmount.toNumber()).to.equal(RECEIVED);
+      expect(vaultAccount.totalDeposited.toNumber()).to.equal(RECEIVED);
+      expect(await balanceOf(vaultTokenAccount, tokenProgram)).to.equal(BigInt(RECEIVED));
+
+      const deposited = findEvent(await eventsOf(program, signature), 'MiningDeposited');
+      expect(deposited.amount.toNumber()).to.equal(RECEIVED);
+    });
+
+    it('create_distributor rejects a deposit the transfer fee would leave short', async () => {
+      const id = Date.now();
+      const distributor = pda(program, ['distributor', jdh.mint, u64Seed(id)]);
+      const expiresAt = (await chainTime(connection)) + 3_600;
+
+      await expectError(
+        program.methods
+          .createDistributor(new BN(id), Array(32).fill(7), new BN(AMOUNT), new BN(1), new BN(expiresAt))
+          .accountsPartial({
+            distributor,
+            vault: pda(program, ['distributor-vault', distributor]),
+            mint: jdh.mint,
+            authorityTokenAccount: userTokenAccount,
+            authority: user.publicKey,
+            tokenProgram,
+            systemProgram: SystemProgram.programId,
+            programConfig,
+          })
+          .signers([user])
+          .rpc({ commitment: 'confirmed' }),
+        'FundingShortfall',
+      );
+    });
+
+    // Escrow vaults keep the fee withheld on the deposit, which must not block closing them
+    describe('escrow', () => {
+      let nextId = Date.now();
+
+      async function createEscrow(recipient: PublicKey): Promise<{ escrow: PublicKey; vault: PublicKey }> {
+        const id = nextId++;
+        const escrow = pda(program, ['escrow', user.publicKey, u64Seed(id)]);
+        const vault = pda(program, ['escrow-vault', escrow]);
+        await program.methods
+          .createEscrow(new BN(id), new BN(AMOUNT), null, null)
+          .accountsPartial({
+            escrow,
+            vault,
+            mint: jdh.mint,
+            recipient,
+            senderTokenAccount: userTokenAccount,
+            sender: user.publicKey,
+            tokenProgram,
+            systemProgram: SystemProgram.programId,
+            programConfig,
+          })
+          .signers([user])
+          .rpc({ commitment: 'confirmed' });
+
+        const escrowAccount = await program.account.escrow.fetch(escrow);
+        expect(escrowAccount.amount.toNumber()).to.equal(RECEIVED);
+        return { escrow, vault };
+      }
+
+      it('release_escrow pays out and closes a vault holding withheld fees', async () => {
+        const recipient = Keypair.generate().publicKey;
+        const recipientTokenAccount = await tokenAccount(jdh.mint, recipient, tokenProgram);
+        const { escrow, vault } = await createEscrow(recipient);
+
+        await program.methods
+          .releaseEscrow()
+          .accountsPartial({
+            escrow,
+            vault,
+            mint: jdh.mint,
+            recipientTokenAccount,
+            sender: user.publicKey,
+            signer: user.publicKey,
+            tokenProgram,
+            programConfig,
+          })
+          .signers([user])
+          .rpc({ commitment: 'confirmed' });
+
+        expect(await balanceOf(recipientTokenAccount, tokenProgram)).to.equal(BigInt(RECEIVED - RECEIVED / 100));
+        expect(await connection.getAccountInfo(vault, 'confirmed')).to.equal(null);
+        expect(await connection.getAccountInfo(escrow, 'confirmed')).to.equal(null);
+      });
+
+      it('refund_escrow pays back and closes a vault holding withheld fees', async () => {
+        const recipient = Keypair.generate();
+        const { escrow, vault } = await createEscrow(recipient.publicKey);
+        const balanceBefore = await balanceOf(userTokenAccount, tokenProgram);
+
+        await program.methods
+          .refundEscrow()
+          .accountsPartial({
+            escrow,
+            vault,
+            mint: jdh.mint,
+            senderTokenAccount: userTokenAccount,
+            sender: user.publicKey,
+            signer: recipient.publicKey,
+            tokenProgram,
+            programConfig,
+          })
+          .signers([recipient])
+          .rpc({ commitment: 'confirmed' });
+
+        expect(await balanceOf(userTokenAccount, tokenProgram)).to.equal(
+          balanceBefore + BigInt(RECEIVED - RECEIVED / 100),
+        );
+        expect(await connection.getAccountInfo(vault, 'confirmed')).to.equal(null);
+        expect(await connection.getAccountInfo(escrow, 'confirmed')).to.equal(null);
+      });
+    });
+
+    it('transfer_tokens delivers the amount net of the Token-2022 fee', async () => {
+      const to = await tokenAccount(jdh.mint, Keypair.generate().publicKey, tokenProgram);
+
+      await program.methods
+        .transferTokens(new BN(AMOUNT))
+        .accountsPartial({
+          feeConfig: pda(program, ['fee-config', jdh.mint]),
+          mint: jdh.mint,
+          from: userTokenAccount,
+          to,
+          treasury: null,
+          authority: user.publicKey,
+          tokenProgram,
+          programConfig,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+      expect(await balanceOf(to, tokenProgram)).to.equal(BigInt(RECEIVED));
+    });
+  });
+});